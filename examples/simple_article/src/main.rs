@@ -24,7 +24,7 @@ fn main() {
     part_two.attach(Element::from(env)).unwrap();
 
 
-    let mut list = List::new(ListMode::Enumerate, &vec!["item 1".to_owned(), "item 2".to_owned(), "item 3".to_owned()]);
+    let list = List::new(ListMode::Enumerate, &["item 1".to_owned(), "item 2".to_owned(), "item 3".to_owned()]);
 
     part_two.attach(Element::from(list)).unwrap();
 
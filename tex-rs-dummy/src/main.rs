@@ -19,12 +19,12 @@ async fn main() {
     part_two.attach(Element::from(chapter)).unwrap();
 
     let mut env = Environment::new("equation");
-    env.1.push("x^2 + y^2 = z^2".to_owned());
+    env.attach_string("x^2 + y^2 = z^2".to_owned());
 
     part_two.attach(Element::from(env)).unwrap();
 
 
-    let mut list = List::new(ListMode::Enumerate, &vec!["item 1".to_owned(), "item 2".to_owned(), "item 3".to_owned()]);
+    let list = List::new(ListMode::Enumerate, &["item 1".to_owned(), "item 2".to_owned(), "item 3".to_owned()]);
 
     part_two.attach(Element::from(list)).unwrap();
 
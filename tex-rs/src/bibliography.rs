@@ -0,0 +1,540 @@
+use crate::error::TexError;
+use crate::latex::Package;
+use crate::reference::arguments;
+use crate::Convert;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+/// Commands that cite bibliography keys
+const CITE_COMMANDS: [&str; 8] = [
+    "cite",
+    "parencite",
+    "textcite",
+    "autocite",
+    "footcite",
+    "citep",
+    "citet",
+    "nocite",
+];
+
+/// Citation commands that need natbib with bibtex
+const NATBIB_COMMANDS: [&str; 2] = ["citep", "citet"];
+
+/// Citation commands that only biblatex defines
+const BIBLATEX_COMMANDS: [&str; 4] = ["parencite", "textcite", "autocite", "footcite"];
+
+/// Fields that bibtex and biber read verbatim, so they are not escaped
+const VERBATIM_FIELDS: [&str; 4] = ["url", "doi", "eprint", "file"];
+
+/// Bibliography of a document, written to `name.bib` next to the `.tex` file
+#[derive(Debug, Clone)]
+pub struct Bibliography {
+    /// Entries written to the `.bib` file
+    pub entries: Vec<BibEntry>,
+    /// How the bibliography is processed and printed
+    pub backend: BibBackend,
+    /// Name of the `.bib` file without the extension
+    pub name: String,
+}
+
+/// Ways of processing a bibliography, with the style used
+/// - Bibtex `(\bibliographystyle{...} and \bibliography{...})`
+/// - Biblatex with biber `(\usepackage[backend=biber, style=...]{biblatex}, \addbibresource{...}
+///   and \printbibliography)`
+#[derive(Debug, Clone)]
+pub enum BibBackend {
+    Bibtex(String),
+    Biblatex(String),
+}
+
+/// Single `.bib` entry with its type, key and fields
+#[derive(Debug, Clone)]
+pub struct BibEntry {
+    /// Entry type `(@article, @book, ...)`
+    pub kind: EntryType,
+    /// Key used to cite the entry
+    pub key: String,
+    /// Fields in the order they are written, values hold LaTeX
+    pub fields: Vec<(String, String)>,
+}
+
+/// Types of entries
+/// - Article `(@article)`
+/// - Book `(@book)`
+/// - InProceedings `(@inproceedings)`
+/// - InCollection `(@incollection)`
+/// - PhdThesis `(@phdthesis)`
+/// - MastersThesis `(@mastersthesis)`
+/// - TechReport `(@techreport)`
+/// - Online `(@online)`, biblatex only
+/// - Misc `(@misc)`
+/// - Custom type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryType {
+    Article,
+    Book,
+    InProceedings,
+    InCollection,
+    PhdThesis,
+    MastersThesis,
+    TechReport,
+    Online,
+    Misc,
+    Custom(String),
+}
+
+/// Cite struct with the kind of citation, the cited keys and an optional note,
+/// used inline in text, cells and captions
+#[derive(Debug, Clone)]
+pub struct Cite(pub CiteKind, pub Vec<String>, pub Option<String>);
+
+/// Kinds of citations
+/// - Cite `(\cite{...})`
+/// - Paren `(\parencite{...})`, needs biblatex
+/// - Text `(\textcite{...})`, needs biblatex
+/// - Citep, a parenthetical citation `(\citep{...})`, needs natbib or biblatex with `natbib=true`
+/// - Citet, a textual citation `(\citet{...})`, needs natbib or biblatex with `natbib=true`
+/// - NoCite, lists the entry without citing it `(\nocite{...})`
+#[derive(Debug, Clone)]
+pub enum CiteKind {
+    Cite,
+    Paren,
+    Text,
+    Citep,
+    Citet,
+    NoCite,
+}
+
+/// Problems found when checking the citations of a document
+/// - Missing: a cited key that is not in the bibliography
+/// - NeedsBiblatex: a citation command that bibtex does not define, like `parencite`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CitationProblem {
+    Missing(String),
+    NeedsBiblatex(String),
+}
+
+// Implementations //
+impl Bibliography {
+    pub fn new(backend: BibBackend) -> Self {
+        Self {
+            entries: Vec::new(),
+            backend,
+            name: "references".to_string(),
+        }
+    }
+    /// Adds an entry, replacing an entry with the same key
+    pub fn add_entry(&mut self, entry: BibEntry) {
+        match self.entries.iter_mut().find(|e| e.key == entry.key) {
+            Some(e) => *e = entry,
+            None => self.entries.push(entry),
+        }
+    }
+    /// Sets the name of the `.bib` file, without the extension
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+    /// Finds the entry with a key
+    pub fn get(&self, key: &str) -> Option<&BibEntry> {
+        self.entries.iter().find(|e| e.key == key)
+    }
+    /// Keys of the entries, in order
+    pub fn keys(&self) -> Vec<String> {
+        self.entries.iter().map(|e| e.key.clone()).collect()
+    }
+    /// Name of the `.bib` file
+    pub fn file_name(&self) -> String {
+        format!("{}.bib", self.name)
+    }
+    /// Contents of the `.bib` file
+    pub fn to_bib_string(&self) -> String {
+        let entries: Vec<String> = self.entries.iter().map(|e| e.to_bib_string()).collect();
+        entries.join("\n\n")
+    }
+    /// Package needed by the backend, biblatex with its options
+    pub fn package(&self) -> Option<Package> {
+        match &self.backend {
+            BibBackend::Bibtex(_) => None,
+            BibBackend::Biblatex(style) => Some(Package::with_options(
+                "biblatex",
+                &["backend=biber", &format!("style={}", style)],
+            )),
+        }
+    }
+    /// Lines for the preamble after the packages, only biblatex needs them
+    pub fn preamble(&self) -> String {
+        match &self.backend {
+            BibBackend::Bibtex(_) => String::new(),
+            BibBackend::Biblatex(_) => format!("\\addbibresource{{{}}}", self.file_name()),
+        }
+    }
+}
+
+impl BibBackend {
+    /// Program that processes the bibliography between runs
+    pub fn as_program_str(&self) -> String {
+        match self {
+            BibBackend::Bibtex(_) => "bibtex".to_string(),
+            BibBackend::Biblatex(_) => "biber".to_string(),
+        }
+    }
+}
+
+impl BibEntry {
+    pub fn new(kind: EntryType, key: &str) -> Self {
+        Self {
+            kind,
+            key: key.to_string(),
+            fields: Vec::new(),
+        }
+    }
+    /// Article in a journal
+    pub fn article(
+        key: &str,
+        author: impl Convert,
+        title: impl Convert,
+        journal: impl Convert,
+        year: u16,
+    ) -> Self {
+        let mut e = Self::new(EntryType::Article, key);
+        e.set("author", author.to_latex_string());
+        e.set("title", title.to_latex_string());
+        e.set("journal", journal.to_latex_string());
+        e.set("year", year.to_string());
+        e
+    }
+    /// Book with a publisher
+    pub fn book(
+        key: &str,
+        author: impl Convert,
+        title: impl Convert,
+        publisher: impl Convert,
+        year: u16,
+    ) -> Self {
+        let mut e = Self::new(EntryType::Book, key);
+        e.set("author", author.to_latex_string());
+        e.set("title", title.to_latex_string());
+        e.set("publisher", publisher.to_latex_string());
+        e.set("year", year.to_string());
+        e
+    }
+    /// Paper in conference proceedings
+    pub fn inproceedings(
+        key: &str,
+        author: impl Convert,
+        title: impl Convert,
+        booktitle: impl Convert,
+        year: u16,
+    ) -> Self {
+        let mut e = Self::new(EntryType::InProceedings, key);
+        e.set("author", author.to_latex_string());
+        e.set("title", title.to_latex_string());
+        e.set("booktitle", booktitle.to_latex_string());
+        e.set("year", year.to_string());
+        e
+    }
+    /// Anything that doesn't fit another type
+    pub fn misc(key: &str, title: impl Convert) -> Self {
+        let mut e = Self::new(EntryType::Misc, key);
+        e.set("title", title.to_latex_string());
+        e
+    }
+    /// Sets a field, escaping it unless it is `Raw` or a field like `url`
+    /// that bibtex and biber read verbatim
+    pub fn set_field(&mut self, name: &str, value: impl Convert + AsRef<str>) {
+        let value = match VERBATIM_FIELDS.contains(&name.to_lowercase().as_str()) {
+            true => value.as_ref().to_string(),
+            false => value.to_latex_string(),
+        };
+        self.set(name, value);
+    }
+    /// Sets a field to a value that is already written
+    fn set(&mut self, name: &str, value: String) {
+        let name = name.to_lowercase();
+        match self.fields.iter_mut().find(|(n, _)| *n == name) {
+            Some(field) => field.1 = value,
+            None => self.fields.push((name, value)),
+        }
+    }
+    /// Value of a field
+    pub fn get(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.fields
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+    }
+    /// Required fields of the entry type that are not set
+    pub fn missing_fields(&self) -> Vec<&'static str> {
+        self.kind
+            .required_fields()
+            .into_iter()
+            .filter(|f| self.get(f).is_none())
+            .collect()
+    }
+    /// Entry as written in a `.bib` file
+    pub fn to_bib_string(&self) -> String {
+        let mut s = vec![format!("@{}{{{},", self.kind.as_type_str(), self.key)];
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(n, v)| format!("  {} = {{{}}}", n, v))
+            .collect();
+        s.push(fields.join(",\n"));
+        s.push("}".to_string());
+        s.join("\n")
+    }
+}
+
+impl EntryType {
+    pub fn as_type_str(&self) -> String {
+        match self {
+            EntryType::Article => "article".to_string(),
+            EntryType::Book => "book".to_string(),
+            EntryType::InProceedings => "inproceedings".to_string(),
+            EntryType::InCollection => "incollection".to_string(),
+            EntryType::PhdThesis => "phdthesis".to_string(),
+            EntryType::MastersThesis => "mastersthesis".to_string(),
+            EntryType::TechReport => "techreport".to_string(),
+            EntryType::Online => "online".to_string(),
+            EntryType::Misc => "misc".to_string(),
+            EntryType::Custom(t) => t.clone(),
+        }
+    }
+    /// Type from the name after `@`, case insensitive
+    pub fn from_type_str(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "article" => EntryType::Article,
+            "book" => EntryType::Book,
+            "inproceedings" | "conference" => EntryType::InProceedings,
+            "incollection" => EntryType::InCollection,
+            "phdthesis" => EntryType::PhdThesis,
+            "mastersthesis" => EntryType::MastersThesis,
+            "techreport" => EntryType::TechReport,
+            "online" => EntryType::Online,
+            "misc" => EntryType::Misc,
+            other => EntryType::Custom(other.to_string()),
+        }
+    }
+    /// Fields BibTeX needs for the type
+    pub fn required_fields(&self) -> Vec<&'static str> {
+        match self {
+            EntryType::Article => vec!["author", "title", "journal", "year"],
+            EntryType::Book => vec!["author", "title", "publisher", "year"],
+            EntryType::InProceedings | EntryType::InCollection => {
+                vec!["author", "title", "booktitle", "year"]
+            }
+            EntryType::PhdThesis | EntryType::MastersThesis => {
+                vec!["author", "title", "school", "year"]
+            }
+            EntryType::TechReport => vec!["author", "title", "institution", "year"],
+            EntryType::Online => vec!["title", "url"],
+            EntryType::Misc | EntryType::Custom(_) => Vec::new(),
+        }
+    }
+}
+
+impl Cite {
+    pub fn new(kind: CiteKind, keys: &[&str]) -> Self {
+        Self(kind, keys.iter().map(|k| k.to_string()).collect(), None)
+    }
+    /// Sets the note, like a page `(\cite[p. 2]{...})`, escaping it unless it is `Raw`
+    pub fn set_note(&mut self, note: impl Convert) {
+        self.2 = Some(note.to_latex_string());
+    }
+}
+
+/// Packages needed by the citations in source written as is, `natbib` for
+/// `\citep` and `\citet`
+pub fn citation_packages(source: &str) -> Vec<String> {
+    match NATBIB_COMMANDS.iter().any(|c| !arguments(source, c).is_empty()) {
+        true => vec!["natbib".to_string()],
+        false => Vec::new(),
+    }
+}
+
+/// Finds the keys cited in LaTeX source, in order without repeats
+pub fn citations(source: &str) -> Vec<String> {
+    let mut v: Vec<String> = Vec::new();
+    for cmd in CITE_COMMANDS {
+        for arg in arguments(source, cmd) {
+            for key in arg.split(',').map(|k| k.trim()) {
+                // `\nocite{*}` lists every entry
+                if !key.is_empty() && key != "*" && !v.iter().any(|k| k == key) {
+                    v.push(key.to_string());
+                }
+            }
+        }
+    }
+    v
+}
+
+/// Checks that every cited key is in the bibliography and that the citation
+/// commands are defined by its backend, every key is missing without one
+pub fn check_citations(source: &str, bibliography: Option<&Bibliography>) -> Vec<CitationProblem> {
+    let keys = bibliography.map(|b| b.keys()).unwrap_or_default();
+    let mut problems: Vec<CitationProblem> = citations(source)
+        .into_iter()
+        .filter(|c| !keys.contains(c))
+        .map(CitationProblem::Missing)
+        .collect();
+    if let Some(BibBackend::Bibtex(_)) = bibliography.map(|b| &b.backend) {
+        for cmd in BIBLATEX_COMMANDS {
+            if !arguments(source, cmd).is_empty() {
+                problems.push(CitationProblem::NeedsBiblatex(cmd.to_string()));
+            }
+        }
+    }
+    problems
+}
+
+/// Parses the entries of a `.bib` file, `@string`, `@preamble` and `@comment`
+/// are skipped and string macros are kept as written, an `@` only starts an
+/// entry at the start of a line or after whitespace
+/// Values joined with `#` are a `ParseError` since the macros they use are not read
+pub fn parse_bib(source: &str) -> Result<Vec<BibEntry>, TexError> {
+    let mut entries = Vec::new();
+    let mut rest = source;
+    while let Some(at) = rest.find('@') {
+        // Text between entries is a comment, like the `@` of an email address
+        let starts_entry = source[..source.len() - rest.len() + at]
+            .chars()
+            .next_back()
+            .is_none_or(|c| c.is_whitespace());
+        rest = &rest[at + 1..];
+        let word = rest.trim_start_matches(|c: char| c.is_alphanumeric());
+        let open = rest.len() - word.trim_start().len();
+        if !starts_entry || word.len() == rest.len() || !rest[open..].starts_with(['{', '(']) {
+            continue;
+        }
+        let kind = rest[..open].trim().to_string();
+        let close = match &rest[open..open + 1] {
+            "{" => '}',
+            _ => ')',
+        };
+        let end = group_end(rest, open, close)
+            .ok_or_else(|| bib_error(source, rest, "unclosed entry"))?;
+        let body = &rest[open + 1..end];
+        rest = &rest[end + 1..];
+        if ["string", "preamble", "comment"].contains(&kind.to_lowercase().as_str()) {
+            continue;
+        }
+        let (key, fields) = body.split_once(',').unwrap_or((body, ""));
+        let mut entry = BibEntry::new(EntryType::from_type_str(&kind), key.trim());
+        for (name, value) in parse_fields(source, fields)? {
+            entry.fields.push((name.to_lowercase(), value));
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Parses a `.bib` file
+pub fn parse_bib_file(path: PathBuf) -> Result<Vec<BibEntry>, TexError> {
+    let source = std::fs::read_to_string(&path).map_err(|e| TexError::io(&path, e))?;
+    parse_bib(&source)
+}
+
+/// Parse error at the start of `at`, a slice of `source`
+fn bib_error(source: &str, at: &str, message: &str) -> TexError {
+    TexError::parse(source, at.as_ptr() as usize - source.as_ptr() as usize, message)
+}
+
+/// Finds the `close` matching the bracket or quote at `open`, skipping nested braces
+fn group_end(s: &str, open: usize, close: char) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in s[open + 1..].char_indices() {
+        match c {
+            c if c == close && depth == 0 => return Some(open + 1 + i),
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses `name = {value}, name = "value", name = 2022` into pairs
+fn parse_fields(source: &str, mut s: &str) -> Result<Vec<(String, String)>, TexError> {
+    let mut v = Vec::new();
+    loop {
+        s = s.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if s.is_empty() {
+            return Ok(v);
+        }
+        let (name, after) = s
+            .split_once('=')
+            .ok_or_else(|| bib_error(source, s, "field without a value"))?;
+        let after = after.trim_start();
+        let (value, rest) = match after.chars().next() {
+            Some('{') => {
+                let end = group_end(after, 0, '}')
+                    .ok_or_else(|| bib_error(source, after, "unclosed field"))?;
+                (&after[1..end], &after[end + 1..])
+            }
+            Some('"') => {
+                let end = group_end(after, 0, '"')
+                    .ok_or_else(|| bib_error(source, after, "unclosed field"))?;
+                (&after[1..end], &after[end + 1..])
+            }
+            _ => {
+                let end = after.find([',', '#']).unwrap_or(after.len());
+                (after[..end].trim(), &after[end..])
+            }
+        };
+        let next = rest.trim_start();
+        if next.starts_with('#') {
+            return Err(bib_error(source, next, "string concatenation with `#` is not supported"));
+        }
+        v.push((name.trim().to_string(), value.to_string()));
+        s = rest;
+    }
+}
+
+// Trait Implementations //
+impl Convert for Cite {
+    fn to_latex_string(&self) -> String {
+        let cmd = match self.0 {
+            CiteKind::Cite => "cite",
+            CiteKind::Paren => "parencite",
+            CiteKind::Text => "textcite",
+            CiteKind::Citep => "citep",
+            CiteKind::Citet => "citet",
+            CiteKind::NoCite => "nocite",
+        };
+        match &self.2 {
+            Some(note) => format!("\\{}[{}]{{{}}}", cmd, note, self.1.join(",")),
+            None => format!("\\{}{{{}}}", cmd, self.1.join(",")),
+        }
+    }
+    /// Packages needed by the kind of citation, `natbib` for `citep` and `citet`
+    fn packages(&self) -> Vec<String> {
+        match self.0 {
+            CiteKind::Citep | CiteKind::Citet => vec!["natbib".to_string()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Display for CitationProblem {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CitationProblem::Missing(k) => write!(f, "missing bibliography entry `{}`", k),
+            CitationProblem::NeedsBiblatex(c) => write!(f, "`\\{}` needs biblatex", c),
+        }
+    }
+}
+
+impl Convert for Bibliography {
+    /// Lines at the end of the document that print the bibliography
+    fn to_latex_string(&self) -> String {
+        match &self.backend {
+            BibBackend::Bibtex(style) => format!(
+                "\\bibliographystyle{{{}}}\n\\bibliography{{{}}}",
+                style, self.name
+            ),
+            BibBackend::Biblatex(_) => "\\printbibliography".to_string(),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use crate::error::TexError;
+use crate::latex::Latex;
+use crate::log::parse_log;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Used to give every compile its own temporary directory
+static COMPILE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Local TeX engines that can compile a document
+/// - Pdflatex `(pdflatex)`
+/// - Xelatex `(xelatex)`
+/// - Lualatex `(lualatex)`
+/// - Latexmk `(latexmk -pdf)`, which does its own reruns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    Pdflatex,
+    Xelatex,
+    Lualatex,
+    Latexmk,
+}
+
+/// Compiler settings used by `Latex::compile`
+#[derive(Debug, Clone)]
+pub struct Compiler {
+    /// The engine, decides the command line arguments
+    pub engine: Engine,
+    /// Program that is run, defaults to the engine's name on `PATH`, a
+    /// relative path is taken from the current directory
+    pub program: PathBuf,
+    /// Maximum number of runs while cross-references settle
+    pub max_runs: u8,
+    /// Program that processes the bibliography, defaults to `bibtex` or `biber`
+    /// on `PATH` depending on the backend, a relative path is taken from the
+    /// current directory
+    pub bib_program: Option<PathBuf>,
+    /// Directory the document is written and compiled in, a new temporary
+    /// directory for every compile when not set
+    pub output_dir: Option<PathBuf>,
+}
+
+// Implementations //
+impl Engine {
+    pub fn as_program_str(&self) -> String {
+        match self {
+            Engine::Pdflatex => "pdflatex".to_string(),
+            Engine::Xelatex => "xelatex".to_string(),
+            Engine::Lualatex => "lualatex".to_string(),
+            Engine::Latexmk => "latexmk".to_string(),
+        }
+    }
+    fn args(&self) -> Vec<&'static str> {
+        match self {
+            Engine::Latexmk => vec!["-pdf", "-interaction=nonstopmode", "-halt-on-error"],
+            _ => vec!["-interaction=nonstopmode", "-halt-on-error", "-file-line-error"],
+        }
+    }
+}
+
+impl Compiler {
+    pub fn new(engine: Engine) -> Self {
+        Self {
+            engine,
+            program: PathBuf::from(engine.as_program_str()),
+            max_runs: 3,
+            bib_program: None,
+            output_dir: None,
+        }
+    }
+    /// Sets the program to run instead of the engine on `PATH`
+    pub fn set_program(&mut self, program: PathBuf) {
+        self.program = program;
+    }
+    /// Sets the maximum number of runs, at least one run is always made
+    pub fn set_max_runs(&mut self, max_runs: u8) {
+        self.max_runs = max_runs;
+    }
+    /// Sets the program that processes the bibliography
+    pub fn set_bib_program(&mut self, program: PathBuf) {
+        self.bib_program = Some(program);
+    }
+    /// Sets the directory the document is written and compiled in
+    pub fn set_output_dir(&mut self, dir: PathBuf) {
+        self.output_dir = Some(dir);
+    }
+    /// Writes the document and its assets to the output directory and compiles it,
+    /// processing the bibliography after the first run and rerunning until
+    /// cross-references settle
+    /// Returns the path of the PDF, or a `LogError` with the diagnostics
+    /// when the engine fails and its log explains why
+    ///
+    /// Without an output directory a new `tex-rs-*` directory is made in the
+    /// system temporary directory, it holds the PDF so it is not removed and
+    /// is left for the caller to clean up
+    pub fn compile(&self, latex: &Latex) -> Result<PathBuf, TexError> {
+        let dir = self.output_dir.clone().unwrap_or_else(temp_dir);
+        std::fs::create_dir_all(&dir).map_err(|e| TexError::io(&dir, e))?;
+        latex.write_with_assets(dir.join("main.tex"), true)?;
+
+        // The programs run in the output directory, so relative paths are resolved first
+        let program = resolve(&self.program)?;
+        let runs = match self.engine {
+            Engine::Latexmk => 1,
+            _ => self.max_runs.max(1),
+        };
+        let mut aux = None;
+        let mut status = None;
+        let mut last_run = 0;
+        for run in 1..=runs {
+            last_run = run;
+            let output = Command::new(&program)
+                .args(self.engine.args())
+                .arg("main.tex")
+                .current_dir(&dir)
+                .output()
+                .map_err(|e| TexError::io(&program, e))?;
+            let log = std::fs::read_to_string(dir.join("main.log")).unwrap_or_default();
+            if !output.status.success() {
+                let diagnostics = parse_log(&log);
+                if diagnostics.iter().any(|d| d.is_error()) {
+                    return Err(TexError::LogError(diagnostics));
+                }
+                return Err(TexError::CompileError {
+                    status: output.status.code(),
+                    run,
+                    log,
+                });
+            }
+            status = output.status.code();
+            if let (1, Some(b), true) = (run, &latex.bibliography, runs > 1) {
+                let bib_program = match &self.bib_program {
+                    Some(p) => resolve(p)?,
+                    None => PathBuf::from(b.backend.as_program_str()),
+                };
+                let output = Command::new(&bib_program)
+                    .arg("main")
+                    .current_dir(&dir)
+                    .output()
+                    .map_err(|e| TexError::io(&bib_program, e))?;
+                if !output.status.success() {
+                    return Err(TexError::CompileError {
+                        status: output.status.code(),
+                        run,
+                        log: std::fs::read_to_string(dir.join("main.blg")).unwrap_or_default(),
+                    });
+                }
+                // The bibliography is only in the document after another run
+                aux = None;
+                continue;
+            }
+            let new_aux = std::fs::read(dir.join("main.aux")).ok();
+            if !needs_rerun(&log) && aux == new_aux {
+                break;
+            }
+            aux = new_aux;
+        }
+
+        let pdf = dir.join("main.pdf");
+        if !pdf.exists() {
+            return Err(TexError::CompileError {
+                status,
+                run: last_run,
+                log: std::fs::read_to_string(dir.join("main.log")).unwrap_or_default(),
+            });
+        }
+        Ok(pdf)
+    }
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self::new(Engine::Pdflatex)
+    }
+}
+
+/// Checks a log for the warnings LaTeX gives when another run is needed
+fn needs_rerun(log: &str) -> bool {
+    log.contains("Rerun to get") || log.contains("Label(s) may have changed")
+}
+
+/// Canonicalizes a relative path to a program, names without a directory
+/// like `pdflatex` are left to be looked up on `PATH`
+fn resolve(program: &Path) -> Result<PathBuf, TexError> {
+    match program.is_relative() && program.components().count() > 1 {
+        true => program.canonicalize().map_err(|e| TexError::io(program, e)),
+        false => Ok(program.to_path_buf()),
+    }
+}
+
+fn temp_dir() -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let count = COMPILE_COUNT.fetch_add(1, Ordering::SeqCst);
+    std::env::temp_dir().join(format!(
+        "tex-rs-{}-{}-{}",
+        std::process::id(),
+        nanos,
+        count
+    ))
+}
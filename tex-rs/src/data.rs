@@ -0,0 +1,189 @@
+use crate::error::TexError;
+use crate::math::{BinOp, Expr, Math};
+use crate::table::{Cell, Column, Table, TableMode};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Read;
+
+/// Options used when building a table from CSV or records
+#[derive(Debug, Clone)]
+pub struct TableOptions {
+    /// Environment the table is written in
+    pub mode: TableMode,
+    /// Columns to keep by their header, in this order, all columns are kept when empty
+    pub columns: Vec<String>,
+    /// Header names to write instead of the original headers
+    pub rename: HashMap<String, String>,
+    /// Number formats for columns, by their original header
+    pub formats: HashMap<String, NumberFormat>,
+}
+
+/// Formats for numeric cells, cells that are not numbers are kept as is
+/// - Fixed with the number of decimals `(3.14)`
+/// - Thousands with separators and the number of decimals `(1,234.50)`
+/// - Percent of a fraction with the number of decimals `(12.5\%)`
+/// - Scientific with the number of decimals `(\(1.23 \times 10^{4}\))`
+#[derive(Debug, Clone, Copy)]
+pub enum NumberFormat {
+    Fixed(usize),
+    Thousands(usize),
+    Percent(usize),
+    Scientific(usize),
+}
+
+// Implementations //
+impl TableOptions {
+    pub fn new(mode: TableMode) -> Self {
+        Self {
+            mode,
+            columns: Vec::new(),
+            rename: HashMap::new(),
+            formats: HashMap::new(),
+        }
+    }
+    /// Sets the columns to keep, in the order they are written
+    pub fn set_columns(&mut self, columns: &[&str]) {
+        self.columns = columns.iter().map(|c| c.to_string()).collect();
+    }
+    /// Writes `to` as the header of the column `from`
+    pub fn rename_column(&mut self, from: &str, to: &str) {
+        self.rename.insert(from.to_string(), to.to_string());
+    }
+    /// Sets the number format of a column
+    pub fn set_format(&mut self, column: &str, format: NumberFormat) {
+        self.formats.insert(column.to_string(), format);
+    }
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self::new(TableMode::Tabular)
+    }
+}
+
+impl NumberFormat {
+    /// Formats a cell, returning `None` if it is not a number
+    pub fn format(&self, cell: &str) -> Option<Cell> {
+        let n: f64 = cell.trim().parse().ok()?;
+        let cell = match *self {
+            NumberFormat::Fixed(d) => Cell::new(format!("{:.*}", d, n)),
+            NumberFormat::Thousands(d) => Cell::new(thousands(&format!("{:.*}", d, n))),
+            NumberFormat::Percent(d) => Cell::new(format!("{:.*}%", d, n * 100.0)),
+            NumberFormat::Scientific(d) => {
+                let s = format!("{:.*e}", d, n);
+                let (mantissa, exponent) = s.split_once('e')?;
+                let power = Expr::num(10).pow(Expr::num(exponent));
+                Cell::new(Math(Expr::num(mantissa).op(BinOp::Times, power)))
+            }
+        };
+        Some(cell)
+    }
+}
+
+impl Table {
+    /// Builds a table from CSV with a header row, numeric columns are aligned right,
+    /// data without any columns is a `DataError`
+    pub fn from_csv<R: Read>(reader: R, options: &TableOptions) -> Result<Table, TexError> {
+        let mut reader = csv::Reader::from_reader(reader);
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+        if headers.is_empty() {
+            return Err(TexError::DataError("the data has no columns".to_string()));
+        }
+        let keep: Vec<usize> = match options.columns.is_empty() {
+            true => (0..headers.len()).collect(),
+            false => options
+                .columns
+                .iter()
+                .map(|c| {
+                    headers
+                        .iter()
+                        .position(|h| h == c)
+                        .ok_or_else(|| TexError::DataError(format!("no column named `{}`", c)))
+                })
+                .collect::<Result<_, _>>()?,
+        };
+        let mut records = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            records.push(
+                keep.iter()
+                    .map(|&i| record.get(i).unwrap_or("").to_string())
+                    .collect::<Vec<String>>(),
+            );
+        }
+
+        let columns: Vec<Column> = (0..keep.len())
+            .map(|i| {
+                let numeric = !records.is_empty()
+                    && records.iter().all(|r| r[i].trim().parse::<f64>().is_ok());
+                match numeric {
+                    true => Column::Right,
+                    false => Column::Left,
+                }
+            })
+            .collect();
+        let mut table = Table::new(options.mode.clone(), &columns);
+        table.add_header(
+            keep.iter()
+                .map(|&i| {
+                    let h = &headers[i];
+                    Cell::new(options.rename.get(h).unwrap_or(h))
+                })
+                .collect(),
+        );
+        for record in records {
+            let cells = keep
+                .iter()
+                .zip(record)
+                .map(|(&i, cell)| {
+                    options
+                        .formats
+                        .get(&headers[i])
+                        .and_then(|f| f.format(&cell))
+                        .unwrap_or_else(|| Cell::new(cell))
+                })
+                .collect();
+            table.add_row(cells);
+        }
+        Ok(table)
+    }
+    /// Builds a table from serde records, the field names become the headers,
+    /// there must be at least one record to get them from
+    pub fn from_records<I, T>(records: I, options: &TableOptions) -> Result<Table, TexError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Serialize,
+    {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for record in records {
+            writer.serialize(record)?;
+        }
+        let data = writer
+            .into_inner()
+            .map_err(|e| TexError::DataError(e.to_string()))?;
+        Table::from_csv(data.as_slice(), options)
+    }
+}
+
+/// Puts thousands separators in the integer part of a formatted number
+fn thousands(number: &str) -> String {
+    let (sign, number) = match number.strip_prefix('-') {
+        Some(n) => ("-", n),
+        None => ("", number),
+    };
+    let (int, frac) = match number.split_once('.') {
+        Some((i, f)) => (i, Some(f)),
+        None => (number, None),
+    };
+    let mut s = String::new();
+    for (i, c) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            s.push(',');
+        }
+        s.push(c);
+    }
+    match frac {
+        Some(f) => format!("{}{}.{}", sign, s, f),
+        None => format!("{}{}", sign, s),
+    }
+}
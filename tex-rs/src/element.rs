@@ -1,4 +1,15 @@
+use crate::bibliography::citation_packages;
+use crate::error::TexError;
+use crate::figure::Figure;
+use crate::frame::Frame;
+use crate::hyperref::link_packages;
+use crate::math::{math_packages, Align, Equation};
+use crate::reference::{reference_packages, VERBATIM_ENVIRONMENTS};
+use crate::span::{RichText, Span};
+use crate::style::Style;
+use crate::table::Table;
 use crate::Convert;
+use std::path::PathBuf;
 
 // Type aliases //
 type Body = String;
@@ -6,39 +17,73 @@ type Meta = String;
 type Pkg = String;
 /// User-Defined Tuple for Body, Meta and Pkg levels 
 pub type UDTuple = (Body, Meta, Pkg);
-/// Item for Environment and List
+/// Item for List, and Environment strings
 pub type Item = String;
 // Structs //
-/// Part struct, contains text and a vec for elements 
+/// Part struct, contains text, a vec for elements, an optional label and numbering
 #[derive(Debug, Clone)]
-pub struct Part(pub String, pub Vec<Element>);
-/// Chapter struct, contains text and a vec for elements 
+pub struct Part(pub String, pub Vec<Element>, pub Option<String>, pub Numbering);
+/// Chapter struct, contains text, a vec for elements, an optional label and numbering
 #[derive(Debug, Clone)]
-pub struct Chapter(pub String, pub Vec<Element>);
-/// Section struct, contains text and a vec for elements 
+pub struct Chapter(pub String, pub Vec<Element>, pub Option<String>, pub Numbering);
+/// Section struct, contains text, a vec for elements, an optional label and numbering
 #[derive(Debug, Clone)]
-pub struct Section(pub String, pub Vec<Element>);
-/// Paragraph struct, contains text and a vec for elements 
+pub struct Section(pub String, pub Vec<Element>, pub Option<String>, pub Numbering);
+/// Subsection struct, contains text, a vec for elements, an optional label and numbering
 #[derive(Debug, Clone)]
-pub struct Paragraph(pub String, pub Option<Vec<Element>>);
-/// Text struct with text and specifid text type 
+pub struct Subsection(pub String, pub Vec<Element>, pub Option<String>, pub Numbering);
+/// Subsubsection struct, contains text, a vec for elements, an optional label and numbering
 #[derive(Debug, Clone)]
-pub struct Text(pub String, pub TextType);
+pub struct Subsubsection(pub String, pub Vec<Element>, pub Option<String>, pub Numbering);
+/// Paragraph struct, contains text, a vec for elements, an optional label and numbering
+#[derive(Debug, Clone)]
+pub struct Paragraph(pub String, pub Vec<Element>, pub Option<String>, pub Numbering);
+/// Subparagraph struct, contains text, a vec for elements, an optional label and numbering
+#[derive(Debug, Clone)]
+pub struct Subparagraph(pub String, pub Vec<Element>, pub Option<String>, pub Numbering);
+/// Text struct with text, specifid text type, a style applied around it and
+/// the packages needed by the typed parts of the text, like a `Ref`
+#[derive(Debug, Clone)]
+pub struct Text(pub String, pub TextType, pub Style, pub Vec<String>);
 /// Input struct with text for filename 
 #[derive(Debug, Clone)]
 pub struct Input(pub String);
-/// Environment struct with text for name, and vec for items (String)
+/// Environment struct with text for name, vec for elements, an optional label,
+/// options `([...])` and arguments `({...})`
 #[derive(Debug, Clone)]
-pub struct Environment(pub String, pub Vec<Item>);
+pub struct Environment(
+    pub String,
+    pub Vec<Element>,
+    pub Option<String>,
+    pub Option<String>,
+    pub Vec<String>,
+);
 /// User-defined command with string and level for it to be in 
 /// - Meta: In the metadata level, just before packages 
 /// - Package: In the level where packages are 
 /// - Body: Inside the document environment
 #[derive(Debug, Clone)]
 pub struct UserDefined(pub String, pub Level);
-/// List struct with ListMode (enumerate or itemize) and vec for items (String)
+/// List struct with ListMode (enumerate, itemize or description), vec for items
+/// and `enumitem` options
+#[derive(Debug, Clone)]
+pub struct List(pub ListMode, pub Vec<ListItem>, pub Vec<ListOption>);
+/// Item of a list with its text, already escaped, and the elements after it,
+/// like a nested list
+#[derive(Debug, Clone)]
+pub struct ListItem {
+    /// Custom label, or the term in a description list `(\item[...])`, already escaped
+    pub label: Option<String>,
+    /// Packages needed by the typed parts of the label and text, like `amsmath` for `Math`
+    pub packages: Vec<String>,
+    /// Beamer overlay spec, like `2-` `(\item<2->)`
+    pub overlay: Option<String>,
+    pub text: Item,
+    pub elements: Vec<Element>,
+}
+/// Raw struct with trusted LaTeX that is written as is, without escaping
 #[derive(Debug, Clone)]
-pub struct List(pub ListMode, pub Vec<Item>);
+pub struct Raw(pub String);
 
 // Enums //
 
@@ -46,23 +91,75 @@ pub struct List(pub ListMode, pub Vec<Item>);
 /// - Part 
 /// - Chapter
 /// - Section (Headers)
+/// - Subsection
+/// - Subsubsection
 /// - Paragraph
+/// - Subparagraph
 /// - Text (Italic, bold, verbatim, etc.)
+/// - RichText (a paragraph of bold, italic, linked and other inline spans)
 /// - Input 
 /// - Environment `(\begin{}...\end{})`
 /// - UserDefiend (any kind of custom code)
-/// - List (enumerate or itemize)
+/// - List (enumerate, itemize or description)
+/// - Table (tabular, tabularx or longtable)
+/// - Figure (images with `\includegraphics`)
+/// - Equation (a single numbered or starred equation)
+/// - Align (equations aligned at their relations)
+/// - Frame (a beamer slide)
+/// - Matter (front, main and back matter or the appendix)
 #[derive(Debug, Clone)]
 pub enum Element {
     Part(Part),
     Chapter(Chapter),
     Section(Section),
+    Subsection(Subsection),
+    Subsubsection(Subsubsection),
     Paragraph(Paragraph),
+    Subparagraph(Subparagraph),
     Text(Text),
+    RichText(RichText),
     Input(Input),
     Environment(Environment),
     UserDefined(UserDefined),
     List(List),
+    Table(Table),
+    Figure(Figure),
+    Equation(Equation),
+    Align(Align),
+    Frame(Frame),
+    Matter(Matter),
+}
+
+/// ElementKind enum with a kind for every element, it states which elements
+/// may contain which:
+/// - Headings contain deeper headings and body elements
+/// - Headings down to Subsubsection also contain frames
+/// - Environments contain any element but matter
+/// - Frames and list items contain body elements
+/// - Matter is only used between top level elements
+/// - Body elements (text, rich text, input, environment, user-defined, list,
+///   table, figure, equation and align) contain nothing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElementKind {
+    Part,
+    Chapter,
+    Section,
+    Subsection,
+    Subsubsection,
+    Paragraph,
+    Subparagraph,
+    Text,
+    RichText,
+    Input,
+    Environment,
+    UserDefined,
+    List,
+    Table,
+    Figure,
+    Equation,
+    Align,
+    Frame,
+    Matter,
 }
 
 /// TextType enum that contains the different kind
@@ -71,9 +168,15 @@ pub enum Element {
 /// - Bold `(\textbf{...})`
 /// - Italic `(\textit{...})`
 /// - Underline `(\underline{...})`
-/// - Verbatim `(\verb!...!)`
+/// - Verbatim `(\verb!...!)`, with another delimiter when the text has a `!`
+///   and the `verbatim` environment when it has every delimiter or a line break
 /// - Roman `(\textrm{...})`
-#[derive(Debug, Clone)]
+/// - SansSerif `(\textsf{...})`
+/// - Monospace `(\texttt{...})`
+/// - SmallCaps `(\textsc{...})`
+/// - Slanted `(\textsl{...})`
+/// - Emphasis `(\emph{...})`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TextType {
     Normal,
     Bold,
@@ -81,14 +184,63 @@ pub enum TextType {
     Underline,
     Verbatim,
     Roman,
+    SansSerif,
+    Monospace,
+    SmallCaps,
+    Slanted,
+    Emphasis,
 }
-/// ListMode enum that contains the two different lists 
+/// ListMode enum that contains the different lists 
 /// - Itemize `(\begin{itemize}...\end{itemize})`
 /// - Enumerate `(\begin{enumerate}...\end{enumerate})`
+/// - Description with a term for each item `(\begin{description}...\end{description})`
 #[derive(Debug, Clone)]
 pub enum ListMode {
     Itemize,
     Enumerate,
+    Description,
+}
+/// `enumitem` options of a list
+/// - Label format, like `\alph*)` `(label=...)`
+/// - NoSep, removes the spacing around and between items `(nosep)`
+/// - ItemSep, space between items `(itemsep=...)`
+/// - TopSep, space above and below the list `(topsep=...)`
+/// - LeftMargin `(leftmargin=...)`
+/// - Start, number of the first item `(start=...)`
+/// - Resume, continues the numbering of the previous list `(resume)`
+/// - Custom option
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListOption {
+    Label(String),
+    NoSep,
+    ItemSep(String),
+    TopSep(String),
+    LeftMargin(String),
+    Start(u32),
+    Resume,
+    Custom(String),
+}
+/// Numbering enum for headings
+/// - Numbered `(\section{...})`
+/// - Unnumbered, also left out of the table of contents `(\section*{...})`
+/// - Short title for the table of contents and page headers `(\section[...]{...})`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Numbering {
+    Numbered,
+    Unnumbered,
+    Short(String),
+}
+/// Matter enum that divides a document, used between top level elements
+/// - Front `(\frontmatter)`, book only
+/// - Main `(\mainmatter)`, book only
+/// - Back `(\backmatter)`, book only
+/// - Appendix, the chapters or sections after it are appendices `(\appendix)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Matter {
+    Front,
+    Main,
+    Back,
+    Appendix,
 }
 /// Level determines where the userdefined command goes 
 /// - Meta: Where metadata goes 
@@ -101,20 +253,185 @@ pub enum Level {
     Package,
 }
 
+// Functions //
+/// Escapes the LaTeX special characters `& % $ # _ { } ~ ^ \` in a string
+pub fn escape(text: &str) -> String {
+    let mut s = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                s.push('\\');
+                s.push(c);
+            }
+            '~' => s.push_str("\\textasciitilde{}"),
+            '^' => s.push_str("\\textasciicircum{}"),
+            '\\' => s.push_str("\\textbackslash{}"),
+            _ => s.push(c),
+        }
+    }
+    s
+}
+
+/// Packages needed by LaTeX written as is, like `Raw` text and `UserDefined`
+/// commands, found by searching it for the commands and environments that need them
+pub(crate) fn raw_packages(source: &str) -> Vec<String> {
+    let mut v = reference_packages(source);
+    v.extend(link_packages(source));
+    v.extend(math_packages(source));
+    v.extend(citation_packages(source));
+    v
+}
+
+/// Delimiters tried in order for `\verb`
+const VERB_DELIMITERS: [char; 7] = ['!', '|', '+', '@', '#', '^', '~'];
+
+/// `\verb` with a delimiter that is not in the text, or the `verbatim`
+/// environment when there is none or the text spans lines
+pub(crate) fn verb(text: &str) -> String {
+    match VERB_DELIMITERS.iter().find(|&&d| !text.contains(d)) {
+        Some(d) if !text.contains('\n') => format!("\\verb{}{}{}", d, text, d),
+        _ => format!("\\begin{{verbatim}}\n{}\n\\end{{verbatim}}", text),
+    }
+}
+
+/// `\label{...}` for an optional label
+fn label(label: &Option<String>) -> String {
+    match label {
+        Some(l) => format!("\\label{{{}}}", l),
+        None => String::new(),
+    }
+}
+
+/// Element followed by its children, for headings inside environments
+fn with_children(e: &Element) -> String {
+    let mut s = vec![e.to_latex_string()];
+    s.extend(e.children().into_iter().flatten().map(with_children));
+    s.join("\n")
+}
+
+/// Heading command with its numbering, unnumbered headings have no label
+/// since there is no number to refer to, `Latex` reports it when checking references
+fn heading(cmd: &str, title: &str, l: &Option<String>, numbering: &Numbering) -> String {
+    match numbering {
+        Numbering::Numbered => format!("\\{}{{{}}}{}", cmd, title, label(l)),
+        Numbering::Unnumbered => format!("\\{}*{{{}}}", cmd, title),
+        Numbering::Short(short) => format!("\\{}[{}]{{{}}}{}", cmd, short, title, label(l)),
+    }
+}
+
 // Implementations //
+impl ElementKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ElementKind::Part => "part",
+            ElementKind::Chapter => "chapter",
+            ElementKind::Section => "section",
+            ElementKind::Subsection => "subsection",
+            ElementKind::Subsubsection => "subsubsection",
+            ElementKind::Paragraph => "paragraph",
+            ElementKind::Subparagraph => "subparagraph",
+            ElementKind::Text => "text",
+            ElementKind::RichText => "rich text",
+            ElementKind::Input => "input",
+            ElementKind::Environment => "environment",
+            ElementKind::UserDefined => "userdefined",
+            ElementKind::List => "list",
+            ElementKind::Table => "table",
+            ElementKind::Figure => "figure",
+            ElementKind::Equation => "equation",
+            ElementKind::Align => "align",
+            ElementKind::Frame => "frame",
+            ElementKind::Matter => "matter",
+        }
+    }
+    /// Depth of a heading, `None` for everything else
+    pub fn level(&self) -> Option<u8> {
+        match self {
+            ElementKind::Part => Some(0),
+            ElementKind::Chapter => Some(1),
+            ElementKind::Section => Some(2),
+            ElementKind::Subsection => Some(3),
+            ElementKind::Subsubsection => Some(4),
+            ElementKind::Paragraph => Some(5),
+            ElementKind::Subparagraph => Some(6),
+            _ => None,
+        }
+    }
+    /// Elements written in the flow of the document
+    pub fn is_body(&self) -> bool {
+        matches!(
+            self,
+            ElementKind::Text
+                | ElementKind::RichText
+                | ElementKind::Input
+                | ElementKind::Environment
+                | ElementKind::UserDefined
+                | ElementKind::List
+                | ElementKind::Table
+                | ElementKind::Figure
+                | ElementKind::Equation
+                | ElementKind::Align
+        )
+    }
+    /// Checks if an element of this kind can contain one of `child`'s kind
+    pub fn can_contain(&self, child: ElementKind) -> bool {
+        match (self.level(), child.level()) {
+            (Some(parent), Some(level)) => level > parent,
+            (Some(parent), None) => {
+                child.is_body() || (child == ElementKind::Frame && parent <= 4)
+            }
+            (None, _) if *self == ElementKind::Environment => child != ElementKind::Matter,
+            (None, _) => {
+                matches!(self, ElementKind::Frame | ElementKind::List) && child.is_body()
+            }
+        }
+    }
+}
+
 impl Element {
-    /// Determines Prioirty rank 
-    pub fn rank(&self) -> u8 {
+    /// Kind of the element, which decides what it can contain
+    pub fn kind(&self) -> ElementKind {
         match self {
-            Element::Part(_) => 0,
-            Element::Chapter(_) => 1,
-            Element::Section(_) => 2,
-            Element::Paragraph(_) => 3,
-            Element::UserDefined(_) => 4,
-            Element::Text(_) => 8,
-            Element::Input(_) => 7,
-            Element::Environment(_) => 6,
-            Element::List(_) => 5,
+            Element::Part(_) => ElementKind::Part,
+            Element::Chapter(_) => ElementKind::Chapter,
+            Element::Section(_) => ElementKind::Section,
+            Element::Subsection(_) => ElementKind::Subsection,
+            Element::Subsubsection(_) => ElementKind::Subsubsection,
+            Element::Paragraph(_) => ElementKind::Paragraph,
+            Element::Subparagraph(_) => ElementKind::Subparagraph,
+            Element::Text(_) => ElementKind::Text,
+            Element::RichText(_) => ElementKind::RichText,
+            Element::Input(_) => ElementKind::Input,
+            Element::Environment(_) => ElementKind::Environment,
+            Element::UserDefined(_) => ElementKind::UserDefined,
+            Element::List(_) => ElementKind::List,
+            Element::Table(_) => ElementKind::Table,
+            Element::Figure(_) => ElementKind::Figure,
+            Element::Equation(_) => ElementKind::Equation,
+            Element::Align(_) => ElementKind::Align,
+            Element::Frame(_) => ElementKind::Frame,
+            Element::Matter(_) => ElementKind::Matter,
+        }
+    }
+    /// Name of the element, used in paths and error messages
+    pub fn name(&self) -> &'static str {
+        self.kind().name()
+    }
+    /// Checks if the element can contain `child`, see `ElementKind::can_contain`
+    pub fn can_contain(&self, child: &Element) -> bool {
+        self.kind().can_contain(child.kind())
+    }
+    /// Gets a reference to the Vec element inside the struct, without cloning
+    pub fn children(&self) -> Option<&Vec<Element>> {
+        match &self {
+            Element::Part(p) => Some(&p.1),
+            Element::Chapter(c) => Some(&c.1),
+            Element::Section(s) => Some(&s.1),
+            Element::Subsection(s) => Some(&s.1),
+            Element::Subsubsection(s) => Some(&s.1),
+            Element::Paragraph(p) => Some(&p.1),
+            Element::Subparagraph(p) => Some(&p.1),
+            _ => None,
         }
     }
     /// Gets the Vec element inside the struct
@@ -123,13 +440,92 @@ impl Element {
             Element::Part(p) => Some(p.to_owned().1),
             Element::Chapter(c) => Some(c.to_owned().1),
             Element::Section(s) => Some(s.to_owned().1),
-            Element::Paragraph(p) => Some(p.to_owned().1.unwrap()),
+            Element::Subsection(s) => Some(s.to_owned().1),
+            Element::Subsubsection(s) => Some(s.to_owned().1),
+            Element::Paragraph(p) => Some(p.to_owned().1),
+            Element::Subparagraph(p) => Some(p.to_owned().1),
             Element::Text(_) => None,
+            Element::RichText(_) => None,
             Element::Input(_) => None,
-            Element::Environment(e) => None,
+            Element::Environment(_) => None,
             Element::UserDefined(_) => None,
             Element::List(_) => None,
+            Element::Table(_) => None,
+            Element::Figure(_) => None,
+            Element::Equation(_) => None,
+            Element::Align(_) => None,
+            Element::Frame(_) => None,
+            Element::Matter(_) => None,
+        }
+    }
+    /// Files the element and its children refer to, like images
+    pub fn assets(&self) -> Vec<PathBuf> {
+        let mut v = match self {
+            Element::Environment(e) => e.assets(),
+            Element::List(l) => l.assets(),
+            Element::Figure(f) => f.assets(),
+            Element::Frame(f) => f.assets(),
+            _ => Vec::new(),
+        };
+        for i in self.children().into_iter().flatten() {
+            v.extend(i.assets());
+        }
+        v
+    }
+    /// Labels of the element and its children that are not written because
+    /// the heading, equation or align they are on is unnumbered
+    pub fn unnumbered_labels(&self) -> Vec<String> {
+        let mut v = match self {
+            Element::Part(Part(_, _, Some(l), Numbering::Unnumbered))
+            | Element::Chapter(Chapter(_, _, Some(l), Numbering::Unnumbered))
+            | Element::Section(Section(_, _, Some(l), Numbering::Unnumbered))
+            | Element::Subsection(Subsection(_, _, Some(l), Numbering::Unnumbered))
+            | Element::Subsubsection(Subsubsection(_, _, Some(l), Numbering::Unnumbered))
+            | Element::Paragraph(Paragraph(_, _, Some(l), Numbering::Unnumbered))
+            | Element::Subparagraph(Subparagraph(_, _, Some(l), Numbering::Unnumbered)) => {
+                vec![l.clone()]
+            }
+            Element::Equation(e) => e.unnumbered_labels(),
+            Element::Align(a) => a.unnumbered_labels(),
+            Element::Figure(f) => f.unnumbered_labels(),
+            Element::Table(t) => t.unnumbered_labels(),
+            Element::Environment(e) => e.1.iter().flat_map(|e| e.unnumbered_labels()).collect(),
+            Element::List(l) => l.elements().iter().flat_map(|e| e.unnumbered_labels()).collect(),
+            Element::Frame(f) => f.elements().iter().flat_map(|e| e.unnumbered_labels()).collect(),
+            _ => Vec::new(),
+        };
+        for i in self.children().into_iter().flatten() {
+            v.extend(i.unnumbered_labels());
         }
+        v
+    }
+    /// Checks that the element and its children can be written, see `Figure::check`
+    pub fn check(&self) -> Result<(), TexError> {
+        if let Element::Figure(f) = self {
+            f.check()?;
+        }
+        let inner: Vec<&Element> = match self {
+            Element::Environment(e) => e.1.iter().collect(),
+            Element::List(l) => l.elements(),
+            Element::Frame(f) => f.elements(),
+            _ => self.children().into_iter().flatten().collect(),
+        };
+        inner.into_iter().try_for_each(|e| e.check())
+    }
+    /// Checks if the element or one inside it has verbatim text, `Verbatim`
+    /// text or spans and verbatim environments like `lstlisting`
+    pub fn has_verbatim(&self) -> bool {
+        let verbatim = match self {
+            Element::Text(t) => t.1 == TextType::Verbatim,
+            Element::RichText(r) => r.0.iter().any(|s| matches!(s, Span::Verbatim(_))),
+            Element::Environment(e) => {
+                VERBATIM_ENVIRONMENTS.contains(&e.0.as_str())
+                    || e.1.iter().any(|e| e.has_verbatim())
+            }
+            Element::List(l) => l.elements().iter().any(|e| e.has_verbatim()),
+            _ => false,
+        };
+        verbatim || self.children().into_iter().flatten().any(|e| e.has_verbatim())
     }
     /// Recursive function to get latex string for all elements inside struct 
     pub fn loop_through(&self) -> String {
@@ -168,29 +564,83 @@ impl Element {
     }
 }
 
-impl Part {
-    pub fn new(text: &str) -> Self {
-        Self(text.to_string(), Vec::new())
-    }
+/// Implements the constructor and setters of a heading, and its conversion
+/// with the heading command of the same name
+macro_rules! heading_impl {
+    ($heading:ident, $cmd:literal) => {
+        impl $heading {
+            pub fn new(text: impl Convert) -> Self {
+                Self(text.to_latex_string(), Vec::new(), None, Numbering::Numbered)
+            }
+            #[doc = concat!("Sets the label used to reference the ", $cmd)]
+            pub fn set_label(&mut self, label: &str) {
+                self.2 = Some(label.to_string());
+            }
+            #[doc = concat!("Makes the ", $cmd, " unnumbered and leaves it out of the table of contents")]
+            pub fn set_unnumbered(&mut self) {
+                self.3 = Numbering::Unnumbered;
+            }
+            /// Sets the title used in the table of contents, escaping it unless it is `Raw`
+            pub fn set_short_title(&mut self, title: impl Convert) {
+                self.3 = Numbering::Short(title.to_latex_string());
+            }
+        }
+
+        impl Convert for $heading {
+            fn to_latex_string(&self) -> String {
+                heading($cmd, &self.0, &self.2, &self.3)
+            }
+        }
+    };
 }
-impl Chapter {
-    pub fn new(text: &str) -> Self {
-        Self(text.to_string(), Vec::new())
+
+heading_impl!(Part, "part");
+heading_impl!(Chapter, "chapter");
+heading_impl!(Section, "section");
+heading_impl!(Subsection, "subsection");
+heading_impl!(Subsubsection, "subsubsection");
+heading_impl!(Paragraph, "paragraph");
+heading_impl!(Subparagraph, "subparagraph");
+
+impl Text {
+    /// Creates text, escaping it unless it is `Raw` or the type is `Verbatim`
+    pub fn new(text: impl Convert + AsRef<str>, text_type: TextType) -> Self {
+        match text_type {
+            TextType::Verbatim => Self(text.as_ref().to_string(), text_type, Style::default(), Vec::new()),
+            _ => Self(text.to_latex_string(), text_type, Style::default(), text.packages()),
+        }
     }
-}
-impl Section {
-    pub fn new(text: &str) -> Self {
-        Self(text.to_string(), Vec::new())
+    /// Creates styled text, escaping it unless it is `Raw`
+    pub fn styled(text: impl Convert, style: Style) -> Self {
+        Self(text.to_latex_string(), TextType::Normal, style, text.packages())
     }
-}
-impl Paragraph {
-    pub fn new(text: &str) -> Self {
-        Self(text.to_string(), Some(Vec::new()))
+    /// Sets the style applied around the text type, `Verbatim` text only
+    /// gets the size and color since `\verb` can't be in a command argument
+    pub fn set_style(&mut self, style: Style) {
+        self.2 = style;
+    }
+    /// Appends to the text, escaping it unless it is `Raw`, used for inline
+    /// parts like a `Ref`
+    pub fn push(&mut self, text: impl Convert) {
+        self.0.push_str(&text.to_latex_string());
+        self.3.extend(text.packages());
     }
 }
-impl Text {
-    pub fn new(text: &str, text_type: TextType) -> Self {
-        Self(text.to_string(), text_type)
+impl TextType {
+    /// Command that wraps the text, `None` for `Normal` and `Verbatim`
+    pub fn command(&self) -> Option<&'static str> {
+        match self {
+            TextType::Normal | TextType::Verbatim => None,
+            TextType::Bold => Some("textbf"),
+            TextType::Italic => Some("textit"),
+            TextType::Underline => Some("underline"),
+            TextType::Roman => Some("textrm"),
+            TextType::SansSerif => Some("textsf"),
+            TextType::Monospace => Some("texttt"),
+            TextType::SmallCaps => Some("textsc"),
+            TextType::Slanted => Some("textsl"),
+            TextType::Emphasis => Some("emph"),
+        }
     }
 }
 impl Input {
@@ -201,17 +651,111 @@ impl Input {
 
 impl Environment {
     pub fn new(text: &str) -> Self {
-        Self(text.to_string(), Vec::new())
+        Self(text.to_string(), Vec::new(), None, None, Vec::new())
+    }
+    /// Sets the label used to reference the environment, like an equation
+    pub fn set_label(&mut self, label: &str) {
+        self.2 = Some(label.to_string());
+    }
+    /// Sets the options as written, like `colback=red!5, title=Note` `(\begin{...}[...])`
+    pub fn set_options(&mut self, options: &str) {
+        self.3 = Some(options.to_string());
+    }
+    /// Adds an argument as written, like `0.5\textwidth` `(\begin{...}{...})`
+    pub fn add_argument(&mut self, argument: &str) {
+        self.4.push(argument.to_string());
     }
     /// Attach string to environment, alternative to attach which requires an Element
+    /// The string is written as is
     pub fn attach_string(&mut self, item: Item) {
-        self.1.push(item);
+        self.1.push(Element::from(UserDefined::new(&item, Level::Body)));
+    }
+    /// Files the elements inside the environment refer to, like images
+    pub fn assets(&self) -> Vec<PathBuf> {
+        self.1.iter().flat_map(|e| e.assets()).collect()
     }
 }
 
 impl List {
-    pub fn new(list_mode: ListMode, items: &Vec<Item>) -> Self {
-        Self(list_mode, items.to_owned())
+    /// Creates a list of items, escaping them
+    pub fn new(list_mode: ListMode, items: &[Item]) -> Self {
+        Self(list_mode, items.iter().map(ListItem::new).collect(), Vec::new())
+    }
+    /// Creates a list of items written as is
+    pub fn raw(list_mode: ListMode, items: &[Item]) -> Self {
+        Self(list_mode, items.iter().map(|i| ListItem::raw(i)).collect(), Vec::new())
+    }
+    /// Adds an item, escaping it unless it is `Raw`
+    pub fn add_item(&mut self, item: impl Convert) {
+        self.1.push(ListItem::new(item));
+    }
+    /// Adds an item shown on the beamer slides of the overlay spec, like `2-`
+    /// for the second slide on `(\item<2-> ...)`, escaping it unless it is `Raw`
+    pub fn add_overlay_item(&mut self, item: impl Convert, overlay: &str) {
+        let mut item = ListItem::new(item);
+        item.set_overlay(overlay);
+        self.1.push(item);
+    }
+    /// Adds an item with a label or elements, like a nested list
+    pub fn add(&mut self, item: ListItem) {
+        self.1.push(item);
+    }
+    /// Adds an `enumitem` option
+    pub fn add_option(&mut self, option: ListOption) {
+        if !self.2.contains(&option) {
+            self.2.push(option);
+        }
+    }
+    /// Elements inside the items, not including the elements of nested lists
+    pub fn elements(&self) -> Vec<&Element> {
+        self.1.iter().flat_map(|i| &i.elements).collect()
+    }
+    /// Files the elements of the items refer to, like images
+    pub fn assets(&self) -> Vec<PathBuf> {
+        self.elements().iter().flat_map(|e| e.assets()).collect()
+    }
+}
+
+impl ListItem {
+    /// Creates an item, escaping the text unless it is `Raw`
+    pub fn new(text: impl Convert) -> Self {
+        let mut item = Self::raw(&text.to_latex_string());
+        item.packages = text.packages();
+        item
+    }
+    /// Creates an item with a label, the term of a description list,
+    /// escaping both unless they are `Raw`
+    pub fn labeled(label: impl Convert, text: impl Convert) -> Self {
+        let mut item = Self::new(text);
+        item.label = Some(label.to_latex_string());
+        item.packages.extend(label.packages());
+        item
+    }
+    /// Creates an item written as is
+    pub fn raw(text: &str) -> Self {
+        Self {
+            label: None,
+            packages: Vec::new(),
+            overlay: None,
+            text: text.to_string(),
+            elements: Vec::new(),
+        }
+    }
+    /// Sets the beamer overlay spec, like `2-` to show the item from the second slide
+    pub fn set_overlay(&mut self, overlay: &str) {
+        self.overlay = Some(overlay.to_string());
+    }
+}
+
+impl Raw {
+    pub fn new(text: &str) -> Self {
+        Self(text.to_string())
+    }
+}
+
+impl AsRef<str> for Raw {
+    fn as_ref(&self) -> &str {
+        &self.0
     }
 }
 
@@ -236,53 +780,53 @@ impl UserDefined {
 }
 
 // Trait Implementations //
-impl Convert for Part {
+impl Convert for str {
     fn to_latex_string(&self) -> String {
-        format!("\\part{{{}}}", &self.0)
+        escape(self)
     }
 }
 
-impl Convert for Chapter {
+impl Convert for String {
     fn to_latex_string(&self) -> String {
-        format!("\\chapter{{{}}}", &self.0)
+        escape(self)
     }
 }
 
-impl Convert for Section {
+impl Convert for Raw {
     fn to_latex_string(&self) -> String {
-        format!("\\section{{{}}}", &self.0)
+        self.0.clone()
     }
 }
 
-impl Convert for Paragraph {
+impl<T: Convert + ?Sized> Convert for &T {
     fn to_latex_string(&self) -> String {
-        format!("\\paragraph{{{}}}", &self.0)
+        (*self).to_latex_string()
+    }
+    fn packages(&self) -> Vec<String> {
+        (*self).packages()
     }
 }
 
 impl Convert for Text {
     fn to_latex_string(&self) -> String {
         let par = "\\par";
-        let mut text = String::new();
-        match &self.1 {
-            TextType::Normal => text = self.0.clone(),
-            TextType::Bold => {
-                text = format!("\\textbf{{{}}}", &self.0);
-            }
-            TextType::Italic => {
-                text = format!("\\textit{{{}}}", &self.0);
-            }
-            TextType::Roman => {
-                text = format!("\\textrm{{{}}}", &self.0);
-            }
-            TextType::Underline => {
-                text = format!("\\underline{{{}}}", &self.0);
-            }
-            TextType::Verbatim => {
-                text = format!("\\verb!{}!", &self.0);
+        let text = match (&self.1, self.1.command()) {
+            (TextType::Verbatim, _) => self.2.apply_declarations(&verb(&self.0)),
+            (_, Some(cmd)) => self.2.apply(&format!("\\{}{{{}}}", cmd, &self.0)),
+            (_, None) => self.2.apply(&self.0),
+        };
+        format!("{} {}", par, text)
+    }
+    /// Packages needed by the style and the parts pushed into the text
+    fn packages(&self) -> Vec<String> {
+        match self.1 {
+            TextType::Verbatim => self.2.declaration_packages(),
+            _ => {
+                let mut v = self.2.packages();
+                v.extend(self.3.iter().cloned());
+                v
             }
         }
-        format!("{} {}", par, &text)
     }
 }
 
@@ -294,16 +838,25 @@ impl Convert for Input {
 
 impl Convert for Environment {
     fn to_latex_string(&self) -> String {
-        let begin = format!("\\begin{{{}}}", &self.0);
+        let mut begin = format!("\\begin{{{}}}", &self.0);
+        if let Some(o) = &self.3 {
+            begin.push_str(&format!("[{}]", o));
+        }
+        for a in &self.4 {
+            begin.push_str(&format!("{{{}}}", a));
+        }
+        begin.push_str(&label(&self.2));
         let end = format!("\\end{{{}}}", &self.0);
         let mut s = Vec::new();
         s.push(begin);
-        for i in &self.1 {
-            s.push(i.to_owned());
-        }
+        s.extend(self.1.iter().map(with_children));
         s.push(end);
         s.join("\n")
     }
+    /// Packages needed by the elements inside the environment
+    fn packages(&self) -> Vec<String> {
+        self.1.iter().flat_map(|e| e.packages()).collect()
+    }
 }
 
 impl Convert for UserDefined {
@@ -312,22 +865,82 @@ impl Convert for UserDefined {
     }
 }
 
+impl Convert for Matter {
+    fn to_latex_string(&self) -> String {
+        match self {
+            Matter::Front => "\\frontmatter".to_string(),
+            Matter::Main => "\\mainmatter".to_string(),
+            Matter::Back => "\\backmatter".to_string(),
+            Matter::Appendix => "\\appendix".to_string(),
+        }
+    }
+}
+
+impl Convert for ListOption {
+    fn to_latex_string(&self) -> String {
+        match self {
+            ListOption::Label(l) => format!("label={}", l),
+            ListOption::NoSep => "nosep".to_string(),
+            ListOption::ItemSep(s) => format!("itemsep={}", s),
+            ListOption::TopSep(s) => format!("topsep={}", s),
+            ListOption::LeftMargin(m) => format!("leftmargin={}", m),
+            ListOption::Start(n) => format!("start={}", n),
+            ListOption::Resume => "resume".to_string(),
+            ListOption::Custom(o) => o.clone(),
+        }
+    }
+}
+
+impl Convert for ListItem {
+    fn to_latex_string(&self) -> String {
+        let mut item = "\\item".to_string();
+        if let Some(o) = &self.overlay {
+            item.push_str(&format!("<{}>", o));
+        }
+        if let Some(l) = &self.label {
+            item.push_str(&format!("[{}]", l));
+        }
+        if !self.text.is_empty() {
+            item.push_str(&format!(" {}", &self.text));
+        }
+        let mut s = vec![item];
+        s.extend(self.elements.iter().map(|e| e.to_latex_string()));
+        s.join("\n")
+    }
+}
+
 impl Convert for List {
     fn to_latex_string(&self) -> String {
         let mode: &str = match &self.0 {
             ListMode::Enumerate => "enumerate",
             ListMode::Itemize => "itemize",
+            ListMode::Description => "description",
         };
 
-        let (begin, end) = (format!("\\begin{{{}}}", mode), format!("\\end{{{}}}", mode));
+        let mut begin = format!("\\begin{{{}}}", mode);
+        if !self.2.is_empty() {
+            let options: Vec<String> = self.2.iter().map(|o| o.to_latex_string()).collect();
+            begin.push_str(&format!("[{}]", options.join(", ")));
+        }
         let mut s = Vec::new();
         s.push(begin);
         for i in &self.1 {
-            s.push(format!("\\item {}", &i));
+            s.push(i.to_latex_string());
         }
-        s.push(end);
+        s.push(format!("\\end{{{}}}", mode));
         s.join("\n")
     }
+    /// Packages needed by the list, its items and their elements,
+    /// `enumitem` for options
+    fn packages(&self) -> Vec<String> {
+        let mut v = match self.2.is_empty() {
+            true => Vec::new(),
+            false => vec!["enumitem".to_string()],
+        };
+        v.extend(self.1.iter().flat_map(|i| i.packages.iter().cloned()));
+        v.extend(self.elements().iter().flat_map(|e| e.packages()));
+        v
+    }
 }
 
 impl Convert for Element {
@@ -336,13 +949,45 @@ impl Convert for Element {
             Element::Part(e) => e.to_latex_string(),
             Element::Chapter(e) => e.to_latex_string(),
             Element::Section(e) => e.to_latex_string(),
+            Element::Subsection(e) => e.to_latex_string(),
+            Element::Subsubsection(e) => e.to_latex_string(),
             Element::Paragraph(e) => e.to_latex_string(),
+            Element::Subparagraph(e) => e.to_latex_string(),
             Element::Text(e) => e.to_latex_string(),
+            // Inline the spans stay in the paragraph around them, as an element
+            // they make up their own
+            Element::RichText(e) => format!("{}\\par", e.to_latex_string()),
             Element::Input(e) => e.to_latex_string(),
             Element::Environment(e) => e.to_latex_string(),
             Element::UserDefined(e) => e.to_latex_string(),
             Element::List(e) => e.to_latex_string(),
+            Element::Table(e) => e.to_latex_string(),
+            Element::Figure(e) => e.to_latex_string(),
+            Element::Equation(e) => e.to_latex_string(),
+            Element::Align(e) => e.to_latex_string(),
+            Element::Frame(e) => e.to_latex_string(),
+            Element::Matter(e) => e.to_latex_string(),
+        }
+    }
+    /// Packages the element and its children need, which `Latex` adds
+    /// when they are missing
+    fn packages(&self) -> Vec<String> {
+        let mut v = match self {
+            Element::Text(t) => t.packages(),
+            Element::RichText(r) => r.packages(),
+            Element::Environment(e) => e.packages(),
+            Element::List(l) => l.packages(),
+            Element::Table(t) => t.packages(),
+            Element::Figure(f) => f.packages(),
+            Element::Frame(f) => f.packages(),
+            Element::Equation(e) => e.packages(),
+            Element::Align(a) => a.packages(),
+            _ => Vec::new(),
+        };
+        for i in self.children().into_iter().flatten() {
+            v.extend(i.packages());
         }
+        v
     }
 }
 
@@ -364,18 +1009,42 @@ impl From<Section> for Element {
     }
 }
 
+impl From<Subsection> for Element {
+    fn from(s: Subsection) -> Self {
+        Element::Subsection(s)
+    }
+}
+
+impl From<Subsubsection> for Element {
+    fn from(s: Subsubsection) -> Self {
+        Element::Subsubsection(s)
+    }
+}
+
 impl From<Paragraph> for Element {
     fn from(p: Paragraph) -> Self {
         Element::Paragraph(p)
     }
 }
 
+impl From<Subparagraph> for Element {
+    fn from(p: Subparagraph) -> Self {
+        Element::Subparagraph(p)
+    }
+}
+
 impl From<Text> for Element {
     fn from(t: Text) -> Self {
         Element::Text(t)
     }
 }
 
+impl From<RichText> for Element {
+    fn from(r: RichText) -> Self {
+        Element::RichText(r)
+    }
+}
+
 impl From<Input> for Element {
     fn from(i: Input) -> Self {
         Element::Input(i)
@@ -401,6 +1070,42 @@ impl From<List> for Element {
 }
 
 
+impl From<Table> for Element {
+    fn from(t: Table) -> Self {
+        Element::Table(t)
+    }
+}
+
+impl From<Figure> for Element {
+    fn from(f: Figure) -> Self {
+        Element::Figure(f)
+    }
+}
+
+impl From<Equation> for Element {
+    fn from(e: Equation) -> Self {
+        Element::Equation(e)
+    }
+}
+
+impl From<Align> for Element {
+    fn from(a: Align) -> Self {
+        Element::Align(a)
+    }
+}
+
+impl From<Frame> for Element {
+    fn from(f: Frame) -> Self {
+        Element::Frame(f)
+    }
+}
+
+impl From<Matter> for Element {
+    fn from(m: Matter) -> Self {
+        Element::Matter(m)
+    }
+}
+
 impl From<Element> for String {
     fn from(e: Element) -> Self {
         e.to_latex_string()
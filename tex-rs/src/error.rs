@@ -1,18 +1,96 @@
+use crate::bibliography::CitationProblem;
+use crate::log::Diagnostic;
+use crate::reference::ReferenceProblem;
 use std::error::Error;
+use std::path::{Path, PathBuf};
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
 
 /// TexError that is used for custom error handling 
 #[derive(Debug)]
 pub enum TexError{
-    /// Error in priority ranking 
-    RankError,
+    /// Element attached to a parent that can't contain it, with their names
+    AttachError {
+        parent: &'static str,
+        child: &'static str,
+    },
+    /// Error from the TeX engine with its exit code, the run it failed on and the log
+    CompileError {
+        status: Option<i32>,
+        run: u8,
+        log: String,
+    },
+    /// Errors found in the log of a failed compile
+    LogError(Vec<Diagnostic>),
+    /// References without a label, labels defined more than once and labels
+    /// on unnumbered elements
+    ReferenceError(Vec<ReferenceProblem>),
+    /// Citations of keys missing from the bibliography or with commands its backend lacks
+    CitationError(Vec<CitationProblem>),
+    /// Error in reading source like a `.bib` file, with the line and column
+    /// it was found on, counting from 1
+    ParseError {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Assets like images that could not be found
+    MissingAssets(Vec<PathBuf>),
+    /// Error in the data used to build an element
+    DataError(String),
+    /// Error in reading CSV or serializing records
+    #[cfg(feature = "csv")]
+    CsvError(csv::Error),
+    /// Error in input/output, with the path it happened on when known
+    IoError {
+        path: Option<PathBuf>,
+        source: std::io::Error,
+    },
 }
 
 impl Display for TexError{
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match *self {
-            TexError::RankError => write!(f, "Rank Error"),
+            TexError::AttachError { parent, child } => {
+                write!(f, "Attach Error: `{}` can't contain `{}`", parent, child)
+            }
+            TexError::CompileError { status, run, .. } => match status {
+                Some(code) => write!(f, "Compile Error: exit code {} on run {}", code, run),
+                None => write!(f, "Compile Error: terminated on run {}", run),
+            },
+            TexError::LogError(ref d) => {
+                let errors: Vec<String> = d
+                    .iter()
+                    .filter(|d| d.is_error())
+                    .map(|d| d.to_string())
+                    .collect();
+                write!(f, "Log Error: {}", errors.join("; "))
+            }
+            TexError::ReferenceError(ref r) => {
+                let problems: Vec<String> = r.iter().map(|p| p.to_string()).collect();
+                write!(f, "Reference Error: {}", problems.join(", "))
+            }
+            TexError::CitationError(ref c) => {
+                let problems: Vec<String> = c.iter().map(|p| p.to_string()).collect();
+                write!(f, "Citation Error: {}", problems.join(", "))
+            }
+            TexError::ParseError {
+                line,
+                column,
+                ref message,
+            } => write!(f, "Parse Error: {} at line {}, column {}", message, line, column),
+            TexError::MissingAssets(ref a) => {
+                let assets: Vec<String> = a.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "Missing Assets: {}", assets.join(", "))
+            }
+            TexError::DataError(ref e) => write!(f, "Data Error: {}", e),
+            #[cfg(feature = "csv")]
+            TexError::CsvError(ref e) => write!(f, "CSV Error: {}", e),
+            TexError::IoError {
+                path: Some(ref path),
+                ref source,
+            } => write!(f, "IO Error: {}: {}", path.display(), source),
+            TexError::IoError { ref source, .. } => write!(f, "IO Error: {}", source),
         }
     }
 }
@@ -20,7 +98,51 @@ impl Display for TexError{
 impl Error for TexError{
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match *self{
-            TexError::RankError => None,
+            TexError::AttachError { .. } => None,
+            TexError::CompileError { .. } => None,
+            TexError::LogError(_) => None,
+            TexError::ReferenceError(_) => None,
+            TexError::CitationError(_) => None,
+            TexError::ParseError { .. } => None,
+            TexError::MissingAssets(_) => None,
+            TexError::DataError(_) => None,
+            #[cfg(feature = "csv")]
+            TexError::CsvError(ref e) => Some(e),
+            TexError::IoError { ref source, .. } => Some(source),
         }
     }
-}
\ No newline at end of file
+}
+
+impl TexError{
+    /// IO error on a path
+    pub(crate) fn io(path: &Path, source: std::io::Error) -> Self {
+        TexError::IoError {
+            path: Some(path.to_path_buf()),
+            source,
+        }
+    }
+    /// Parse error at a byte offset of the source
+    pub(crate) fn parse(source: &str, offset: usize, message: &str) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        TexError::ParseError {
+            line,
+            column: before[line_start..].chars().count() + 1,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<std::io::Error> for TexError{
+    fn from(source: std::io::Error) -> Self {
+        TexError::IoError { path: None, source }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<csv::Error> for TexError{
+    fn from(e: csv::Error) -> Self {
+        TexError::CsvError(e)
+    }
+}
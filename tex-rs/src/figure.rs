@@ -0,0 +1,228 @@
+use crate::error::TexError;
+use crate::Convert;
+use std::path::PathBuf;
+
+/// Characters that break the path of `\includegraphics`
+const PATH_SPECIALS: [char; 8] = ['%', '#', '{', '}', '~', '^', '&', '$'];
+
+/// Figure struct with one image, or several images written as subfigures,
+/// a figure without images or with a path `\includegraphics` can't read is
+/// a `DataError` when writing
+#[derive(Debug, Clone)]
+pub struct Figure {
+    /// Images in the figure, more than one are written as subfigures
+    pub images: Vec<Image>,
+    /// `\caption{...}`, already escaped
+    pub caption: Option<String>,
+    /// Packages needed by the typed parts of the caption, like `amsmath` for `Math`
+    pub caption_packages: Vec<String>,
+    /// `\label{...}`, only written with a caption to number
+    pub label: Option<String>,
+    /// Float placement `(\begin{figure}[...])`
+    pub placement: Option<String>,
+}
+
+/// Image included with `\includegraphics[...]{path}`
+#[derive(Debug, Clone)]
+pub struct Image {
+    /// Path of the image file, written as is so it can't contain TeX special
+    /// characters like `%` and `#`, relative paths are
+    /// relative to the current directory when assets are checked or copied
+    pub path: PathBuf,
+    /// `width=...`
+    pub width: Option<String>,
+    /// `height=...`
+    pub height: Option<String>,
+    /// `scale=...`
+    pub scale: Option<f64>,
+    /// Caption of the subfigure, already escaped
+    pub caption: Option<String>,
+    /// Packages needed by the typed parts of the caption
+    pub caption_packages: Vec<String>,
+    /// Label of the subfigure, only written with a caption to number
+    pub label: Option<String>,
+}
+
+// Implementations //
+impl Figure {
+    pub fn new(image: Image) -> Self {
+        Self {
+            images: vec![image],
+            caption: None,
+            caption_packages: Vec::new(),
+            label: None,
+            placement: None,
+        }
+    }
+    /// Adds another image, making the images subfigures
+    pub fn add_image(&mut self, image: Image) {
+        self.images.push(image);
+    }
+    /// Sets the caption, escaping it unless it is `Raw`
+    pub fn set_caption(&mut self, caption: impl Convert) {
+        self.caption = Some(caption.to_latex_string());
+        self.caption_packages = caption.packages();
+    }
+    /// Sets the label used to reference the figure
+    pub fn set_label(&mut self, label: &str) {
+        self.label = Some(label.to_string());
+    }
+    /// Sets the float placement, like `htbp`
+    pub fn set_placement(&mut self, placement: &str) {
+        self.placement = Some(placement.to_string());
+    }
+    /// Labels that are not written because the figure or subfigure they are
+    /// on has no caption, so there is no number to refer to
+    pub fn unnumbered_labels(&self) -> Vec<String> {
+        let subfigures: &[Image] = match self.images.len() {
+            1 => &[],
+            _ => &self.images,
+        };
+        let labeled = subfigures.iter().map(|i| (&i.caption, &i.label));
+        labeled
+            .chain([(&self.caption, &self.label)])
+            .filter_map(|(c, l)| match (c, l) {
+                (None, Some(l)) => Some(l.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+    /// Checks that the figure has an image and that the image paths can be
+    /// written, `\includegraphics` can't read paths with TeX special characters
+    pub fn check(&self) -> Result<(), TexError> {
+        if self.images.is_empty() {
+            return Err(TexError::DataError("figure without images".to_string()));
+        }
+        let special = |c: char| PATH_SPECIALS.contains(&c);
+        match self.images.iter().find(|i| i.path.to_string_lossy().contains(special)) {
+            Some(i) => Err(TexError::DataError(format!(
+                "image path `{}` has one of the characters {}",
+                i.path.display(),
+                PATH_SPECIALS.iter().collect::<String>()
+            ))),
+            None => Ok(()),
+        }
+    }
+    /// Image files the figure refers to
+    pub fn assets(&self) -> Vec<PathBuf> {
+        self.images.iter().map(|i| i.path.clone()).collect()
+    }
+}
+
+impl Image {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: PathBuf::from(path),
+            width: None,
+            height: None,
+            scale: None,
+            caption: None,
+            caption_packages: Vec::new(),
+            label: None,
+        }
+    }
+    /// Sets the width, like `0.5\textwidth`
+    pub fn set_width(&mut self, width: &str) {
+        self.width = Some(width.to_string());
+    }
+    /// Sets the height, like `4cm`
+    pub fn set_height(&mut self, height: &str) {
+        self.height = Some(height.to_string());
+    }
+    /// Sets the scale
+    pub fn set_scale(&mut self, scale: f64) {
+        self.scale = Some(scale);
+    }
+    /// Sets the subfigure caption, escaping it unless it is `Raw`
+    pub fn set_caption(&mut self, caption: impl Convert) {
+        self.caption = Some(caption.to_latex_string());
+        self.caption_packages = caption.packages();
+    }
+    /// Sets the label used to reference the subfigure
+    pub fn set_label(&mut self, label: &str) {
+        self.label = Some(label.to_string());
+    }
+    fn options(&self) -> Vec<String> {
+        let mut v = Vec::new();
+        if let Some(w) = &self.width {
+            v.push(format!("width={}", w));
+        }
+        if let Some(h) = &self.height {
+            v.push(format!("height={}", h));
+        }
+        if let Some(s) = self.scale {
+            v.push(format!("scale={}", s));
+        }
+        v
+    }
+}
+
+/// `\caption{...}` followed by the label, a label without a caption is
+/// left out since it would refer to the enclosing section
+fn caption(caption: &Option<String>, label: &Option<String>) -> Option<String> {
+    let caption = format!("\\caption{{{}}}", caption.as_ref()?);
+    match label {
+        Some(l) => Some(format!("{}\n\\label{{{}}}", caption, l)),
+        None => Some(caption),
+    }
+}
+
+// Trait Implementations //
+impl Convert for Image {
+    fn to_latex_string(&self) -> String {
+        let path = self.path.to_string_lossy().replace('\\', "/");
+        let options = self.options();
+        match options.is_empty() {
+            true => format!("\\includegraphics{{{}}}", path),
+            false => format!("\\includegraphics[{}]{{{}}}", options.join(", "), path),
+        }
+    }
+}
+
+impl Convert for Figure {
+    fn to_latex_string(&self) -> String {
+        let mut s = Vec::new();
+        match &self.placement {
+            Some(p) => s.push(format!("\\begin{{figure}}[{}]", p)),
+            None => s.push("\\begin{figure}".to_string()),
+        }
+        s.push("\\centering".to_string());
+        match self.images.as_slice() {
+            [image] => s.push(image.to_latex_string()),
+            images => {
+                // Leave a little room for the \hfill between subfigures
+                let width = 0.95 / images.len() as f64;
+                let mut subfigures = Vec::new();
+                for image in images {
+                    let mut sub = vec![format!("\\begin{{subfigure}}{{{:.2}\\textwidth}}", width)];
+                    sub.push("\\centering".to_string());
+                    match image.options().is_empty() {
+                        true => {
+                            let mut full = image.clone();
+                            full.set_width("\\linewidth");
+                            sub.push(full.to_latex_string());
+                        }
+                        false => sub.push(image.to_latex_string()),
+                    }
+                    sub.extend(caption(&image.caption, &image.label));
+                    sub.push("\\end{subfigure}".to_string());
+                    subfigures.push(sub.join("\n"));
+                }
+                s.push(subfigures.join("\n\\hfill\n"));
+            }
+        }
+        s.extend(caption(&self.caption, &self.label));
+        s.push("\\end{figure}".to_string());
+        s.join("\n")
+    }
+    /// Packages needed to write the figure and the typed parts of its captions
+    fn packages(&self) -> Vec<String> {
+        let mut v = vec!["graphicx".to_string()];
+        if self.images.len() > 1 {
+            v.push("subcaption".to_string());
+        }
+        let captions = self.images.iter().map(|i| &i.caption_packages);
+        v.extend(captions.chain([&self.caption_packages]).flatten().cloned());
+        v
+    }
+}
@@ -0,0 +1,292 @@
+use crate::element::{Element, ElementKind};
+use crate::error::TexError;
+use crate::traits::check;
+use crate::Convert;
+use std::path::PathBuf;
+
+/// Beamer frame `(\begin{frame}...\end{frame})` with its title and content
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// Frame title, already escaped
+    pub title: Option<String>,
+    /// Frame subtitle, already escaped
+    pub subtitle: Option<String>,
+    /// Options `(\begin{frame}[...])`
+    pub options: Vec<FrameOption>,
+    /// Content in the order it is shown
+    pub content: Vec<FrameContent>,
+}
+
+/// Frame options
+/// - Fragile, needed for verbatim text and added for it `(fragile)`
+/// - AllowFrameBreaks, splits long content over several slides `(allowframebreaks)`
+/// - Plain, without headline and footline `(plain)`
+/// - Shrink, shrinks content to fit `(shrink)`
+/// - Label to refer to the frame `(label=...)`
+/// - Custom option
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameOption {
+    Fragile,
+    AllowFrameBreaks,
+    Plain,
+    Shrink,
+    Label(String),
+    Custom(String),
+}
+
+/// Content of a frame or a column
+/// - Element, like text, lists, figures and tables
+/// - Pause, shows what follows on the next slide `(\pause)`
+/// - Block `(\begin{block}...)`
+/// - Columns `(\begin{columns}...)`
+#[derive(Debug, Clone)]
+pub enum FrameContent {
+    Element(Element),
+    Pause,
+    Block(Block),
+    Columns(Vec<FrameColumn>),
+}
+
+/// Block with a title, shown on the slides of its overlay spec
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub kind: BlockKind,
+    /// Block title, already escaped
+    pub title: String,
+    pub content: Vec<Element>,
+    /// Overlay spec, like `2-` `(\begin{block}<2->...)`
+    pub overlay: Option<String>,
+}
+
+/// Kinds of blocks
+/// - Block `(block)`
+/// - Alert `(alertblock)`
+/// - Example `(exampleblock)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockKind {
+    Block,
+    Alert,
+    Example,
+}
+
+/// Column of a frame with its width `(\begin{column}{...})`
+#[derive(Debug, Clone)]
+pub struct FrameColumn {
+    /// Width, like `0.5\textwidth`
+    pub width: String,
+    pub content: Vec<FrameContent>,
+}
+
+// Implementations //
+impl Frame {
+    /// Creates a frame, escaping the title unless it is `Raw`
+    pub fn new(title: impl Convert) -> Self {
+        Self {
+            title: Some(title.to_latex_string()),
+            subtitle: None,
+            options: Vec::new(),
+            content: Vec::new(),
+        }
+    }
+    /// Creates a frame without a title
+    pub fn untitled() -> Self {
+        Self {
+            title: None,
+            subtitle: None,
+            options: Vec::new(),
+            content: Vec::new(),
+        }
+    }
+    /// Sets the subtitle, escaping it unless it is `Raw`
+    pub fn set_subtitle(&mut self, subtitle: impl Convert) {
+        self.subtitle = Some(subtitle.to_latex_string());
+    }
+    /// Adds an option
+    pub fn add_option(&mut self, option: FrameOption) {
+        if !self.options.contains(&option) {
+            self.options.push(option);
+        }
+    }
+    /// Adds an element, returns an `AttachError` when a frame can't contain it
+    pub fn add(&mut self, element: Element) -> Result<(), TexError> {
+        check(ElementKind::Frame, &element)?;
+        self.content.push(FrameContent::Element(element));
+        Ok(())
+    }
+    /// Adds a `\pause`, the content after it is shown on the next slide
+    pub fn pause(&mut self) {
+        self.content.push(FrameContent::Pause);
+    }
+    /// Adds a block
+    pub fn add_block(&mut self, block: Block) {
+        self.content.push(FrameContent::Block(block));
+    }
+    /// Adds columns side by side
+    pub fn add_columns(&mut self, columns: Vec<FrameColumn>) {
+        self.content.push(FrameContent::Columns(columns));
+    }
+    /// Elements of the frame, including the ones in blocks and columns
+    pub fn elements(&self) -> Vec<&Element> {
+        let mut v = Vec::new();
+        for c in &self.content {
+            c.collect_elements(&mut v);
+        }
+        v
+    }
+    /// Files the elements of the frame refer to, like images
+    pub fn assets(&self) -> Vec<PathBuf> {
+        self.elements().iter().flat_map(|e| e.assets()).collect()
+    }
+}
+
+impl FrameContent {
+    fn collect_elements<'a>(&'a self, v: &mut Vec<&'a Element>) {
+        match self {
+            FrameContent::Element(e) => v.push(e),
+            FrameContent::Pause => {}
+            FrameContent::Block(b) => v.extend(&b.content),
+            FrameContent::Columns(columns) => {
+                for c in columns.iter().flat_map(|c| &c.content) {
+                    c.collect_elements(v);
+                }
+            }
+        }
+    }
+}
+
+impl Block {
+    /// Creates a block, escaping the title unless it is `Raw`
+    pub fn new(kind: BlockKind, title: impl Convert) -> Self {
+        Self {
+            kind,
+            title: title.to_latex_string(),
+            content: Vec::new(),
+            overlay: None,
+        }
+    }
+    /// Adds an element, returns an `AttachError` when a frame can't contain it
+    pub fn add(&mut self, element: Element) -> Result<(), TexError> {
+        check(ElementKind::Frame, &element)?;
+        self.content.push(element);
+        Ok(())
+    }
+    /// Sets the overlay spec, like `2-` to show the block from the second slide
+    pub fn set_overlay(&mut self, overlay: &str) {
+        self.overlay = Some(overlay.to_string());
+    }
+}
+
+impl FrameColumn {
+    pub fn new(width: &str) -> Self {
+        Self {
+            width: width.to_string(),
+            content: Vec::new(),
+        }
+    }
+    /// Adds an element, returns an `AttachError` when a frame can't contain it
+    pub fn add(&mut self, element: Element) -> Result<(), TexError> {
+        check(ElementKind::Frame, &element)?;
+        self.content.push(FrameContent::Element(element));
+        Ok(())
+    }
+    /// Adds a `\pause`
+    pub fn pause(&mut self) {
+        self.content.push(FrameContent::Pause);
+    }
+    /// Adds a block
+    pub fn add_block(&mut self, block: Block) {
+        self.content.push(FrameContent::Block(block));
+    }
+}
+
+// Trait Implementations //
+impl Convert for FrameOption {
+    fn to_latex_string(&self) -> String {
+        match self {
+            FrameOption::Fragile => "fragile".to_string(),
+            FrameOption::AllowFrameBreaks => "allowframebreaks".to_string(),
+            FrameOption::Plain => "plain".to_string(),
+            FrameOption::Shrink => "shrink".to_string(),
+            FrameOption::Label(l) => format!("label={}", l),
+            FrameOption::Custom(o) => o.clone(),
+        }
+    }
+}
+
+impl Convert for FrameContent {
+    fn to_latex_string(&self) -> String {
+        match self {
+            FrameContent::Element(e) => e.to_latex_string(),
+            FrameContent::Pause => "\\pause".to_string(),
+            FrameContent::Block(b) => b.to_latex_string(),
+            FrameContent::Columns(columns) => {
+                let mut s = vec!["\\begin{columns}".to_string()];
+                s.extend(columns.iter().map(|c| c.to_latex_string()));
+                s.push("\\end{columns}".to_string());
+                s.join("\n")
+            }
+        }
+    }
+}
+
+impl Convert for Block {
+    fn to_latex_string(&self) -> String {
+        let env = match self.kind {
+            BlockKind::Block => "block",
+            BlockKind::Alert => "alertblock",
+            BlockKind::Example => "exampleblock",
+        };
+        let overlay = match &self.overlay {
+            Some(o) => format!("<{}>", o),
+            None => String::new(),
+        };
+        let mut s = vec![format!("\\begin{{{}}}{}{{{}}}", env, overlay, self.title)];
+        s.extend(self.content.iter().map(|e| e.to_latex_string()));
+        s.push(format!("\\end{{{}}}", env));
+        s.join("\n")
+    }
+}
+
+impl Convert for FrameColumn {
+    fn to_latex_string(&self) -> String {
+        let mut s = vec![format!("\\begin{{column}}{{{}}}", self.width)];
+        s.extend(self.content.iter().map(|c| c.to_latex_string()));
+        s.push("\\end{column}".to_string());
+        s.join("\n")
+    }
+}
+
+impl Convert for Frame {
+    fn to_latex_string(&self) -> String {
+        let content: Vec<String> = self.content.iter().map(|c| c.to_latex_string()).collect();
+        let content = content.join("\n");
+        let mut options = self.options.clone();
+        // Verbatim text only works in fragile frames
+        let verbatim = self.elements().iter().any(|e| e.has_verbatim());
+        if verbatim && !options.contains(&FrameOption::Fragile) {
+            options.push(FrameOption::Fragile);
+        }
+        let mut begin = "\\begin{frame}".to_string();
+        if !options.is_empty() {
+            let options: Vec<String> = options.iter().map(|o| o.to_latex_string()).collect();
+            begin.push_str(&format!("[{}]", options.join(", ")));
+        }
+        match (&self.title, &self.subtitle) {
+            (Some(t), Some(s)) => begin.push_str(&format!("{{{}}}{{{}}}", t, s)),
+            (None, Some(s)) => begin.push_str(&format!("{{}}{{{}}}", s)),
+            (Some(t), None) => begin.push_str(&format!("{{{}}}", t)),
+            (None, None) => {}
+        }
+        format!("{}\n{}\n\\end{{frame}}", begin, content)
+    }
+    /// Packages needed by the elements of the frame
+    fn packages(&self) -> Vec<String> {
+        self.elements().iter().flat_map(|e| e.packages()).collect()
+    }
+}
+
+impl From<Element> for FrameContent {
+    fn from(e: Element) -> Self {
+        FrameContent::Element(e)
+    }
+}
@@ -0,0 +1,190 @@
+use crate::element::escape;
+use crate::reference::arguments;
+use crate::Convert;
+
+/// Link struct with the url and the text shown for it, used inline in text,
+/// cells and captions
+#[derive(Debug, Clone)]
+pub struct Link(pub String, pub String);
+
+/// Url struct with a url shown as is `(\url{...})`
+#[derive(Debug, Clone)]
+pub struct Url(pub String);
+
+/// `hyperref` setup `(\hypersetup{...})`, when the PDF title or author is not
+/// set `hyperref` takes it from `\title` and `\author` `(pdfusetitle)`
+#[derive(Debug, Clone, Default)]
+pub struct Hyperref {
+    /// Colors the text of links instead of boxing them `(colorlinks)`
+    pub color_links: bool,
+    /// Color of links to labels `(linkcolor=...)`
+    pub link_color: Option<String>,
+    /// Color of citations `(citecolor=...)`
+    pub cite_color: Option<String>,
+    /// Color of urls `(urlcolor=...)`
+    pub url_color: Option<String>,
+    /// PDF bookmarks for the headings `(bookmarks=...)`, a package option
+    /// so it is not used with beamer, which loads `hyperref` itself
+    pub bookmarks: Option<bool>,
+    /// Opens the bookmarks in the viewer `(bookmarksopen)`
+    pub bookmarks_open: bool,
+    /// Numbers the bookmarks like the headings `(bookmarksnumbered)`
+    pub bookmarks_numbered: bool,
+    /// `pdftitle`, plain text escaped when written
+    pub title: Option<String>,
+    /// `pdfauthor`, plain text escaped when written
+    pub author: Option<String>,
+    /// `pdfsubject`, plain text escaped when written
+    pub subject: Option<String>,
+    /// `pdfkeywords`, plain text escaped when written
+    pub keywords: Vec<String>,
+}
+
+// Functions //
+/// Escapes a url so it can be used in an argument, `#` and `%` with a
+/// backslash and `\`, `{`, `}` and `~` percent-encoded
+pub fn escape_url(url: &str) -> String {
+    let mut s = String::with_capacity(url.len());
+    for c in url.chars() {
+        match c {
+            '%' | '#' => {
+                s.push('\\');
+                s.push(c);
+            }
+            '\\' | '{' | '}' | '~' => s.push_str(&format!("\\%{:02X}", c as u32)),
+            _ => s.push(c),
+        }
+    }
+    s
+}
+
+/// Packages needed by the links in source written as is, `hyperref` for `\href` and `\url`
+pub fn link_packages(source: &str) -> Vec<String> {
+    match ["href", "url"].iter().any(|c| !arguments(source, c).is_empty()) {
+        true => vec!["hyperref".to_string()],
+        false => Vec::new(),
+    }
+}
+
+// Implementations //
+impl Link {
+    /// Creates a link, escaping the url and the text unless it is `Raw`
+    pub fn new(url: &str, text: impl Convert) -> Self {
+        Self(escape_url(url), text.to_latex_string())
+    }
+}
+
+impl Url {
+    pub fn new(url: &str) -> Self {
+        Self(escape_url(url))
+    }
+}
+
+impl Hyperref {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Colors links, citations and urls instead of boxing them
+    pub fn set_link_colors(&mut self, link: &str, cite: &str, url: &str) {
+        self.color_links = true;
+        self.link_color = Some(link.to_string());
+        self.cite_color = Some(cite.to_string());
+        self.url_color = Some(url.to_string());
+    }
+    pub fn set_color_links(&mut self, color_links: bool) {
+        self.color_links = color_links;
+    }
+    pub fn set_bookmarks(&mut self, bookmarks: bool) {
+        self.bookmarks = Some(bookmarks);
+    }
+    pub fn set_bookmarks_open(&mut self, open: bool) {
+        self.bookmarks_open = open;
+    }
+    pub fn set_bookmarks_numbered(&mut self, numbered: bool) {
+        self.bookmarks_numbered = numbered;
+    }
+    /// Sets the PDF title, plain text escaped for the PDF string
+    pub fn set_title(&mut self, title: &str) {
+        self.title = Some(title.to_string());
+    }
+    /// Sets the PDF author, plain text escaped for the PDF string
+    pub fn set_author(&mut self, author: &str) {
+        self.author = Some(author.to_string());
+    }
+    /// Sets the PDF subject, plain text escaped for the PDF string
+    pub fn set_subject(&mut self, subject: &str) {
+        self.subject = Some(subject.to_string());
+    }
+    /// Adds a PDF keyword, plain text escaped for the PDF string
+    pub fn add_keyword(&mut self, keyword: &str) {
+        self.keywords.push(keyword.to_string());
+    }
+    /// Options that only work when loading `hyperref`
+    pub fn package_options(&self) -> Vec<String> {
+        let mut v = Vec::new();
+        if let Some(b) = self.bookmarks {
+            v.push(format!("bookmarks={}", b));
+        }
+        if self.title.is_none() || self.author.is_none() {
+            v.push("pdfusetitle".to_string());
+        }
+        v
+    }
+    /// `\hypersetup{...}`
+    pub fn setup(&self) -> String {
+        let mut v = Vec::new();
+        if self.color_links {
+            v.push("colorlinks=true".to_string());
+        }
+        let colors = [
+            ("linkcolor", &self.link_color),
+            ("citecolor", &self.cite_color),
+            ("urlcolor", &self.url_color),
+        ];
+        for (name, color) in colors {
+            if let Some(c) = color {
+                v.push(format!("{}={}", name, c));
+            }
+        }
+        if self.bookmarks_open {
+            v.push("bookmarksopen=true".to_string());
+        }
+        if self.bookmarks_numbered {
+            v.push("bookmarksnumbered=true".to_string());
+        }
+        let keywords = self.keywords.join(", ");
+        let properties = [
+            ("pdftitle", self.title.as_ref()),
+            ("pdfauthor", self.author.as_ref()),
+            ("pdfsubject", self.subject.as_ref()),
+            ("pdfkeywords", Some(&keywords)),
+        ];
+        for (name, value) in properties {
+            if let Some(value) = value.filter(|v| !v.is_empty()) {
+                v.push(format!("{}={{{}}}", name, escape(value)));
+            }
+        }
+        format!("\\hypersetup{{{}}}", v.join(", "))
+    }
+}
+
+// Trait Implementations //
+impl Convert for Link {
+    fn to_latex_string(&self) -> String {
+        format!("\\href{{{}}}{{{}}}", &self.0, &self.1)
+    }
+    /// Packages needed by the link, `hyperref`
+    fn packages(&self) -> Vec<String> {
+        vec!["hyperref".to_string()]
+    }
+}
+
+impl Convert for Url {
+    fn to_latex_string(&self) -> String {
+        format!("\\url{{{}}}", &self.0)
+    }
+    /// Packages needed by the url, `hyperref`
+    fn packages(&self) -> Vec<String> {
+        vec!["hyperref".to_string()]
+    }
+}
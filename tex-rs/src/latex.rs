@@ -1,11 +1,19 @@
-use crate::element::{Element, UDTuple};
+use crate::bibliography::{check_citations, BibBackend, Bibliography, CitationProblem};
+use crate::compile::Compiler;
+use crate::element::{raw_packages, Element, UDTuple};
+use crate::error::TexError;
+use crate::hyperref::Hyperref;
+use crate::layout::PageLayout;
+use crate::reference::{check_references, labels, ReferenceProblem};
+use crate::source_map::{ElementPath, SourceMap};
+use crate::style::Color;
 use crate::Convert;
 use async_std::fs::File;
 use async_std::io::WriteExt;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
-type Doc = (Class, Option<u8>, Option<String>);
+type Doc = (Class, Option<u8>, Option<String>, Vec<ClassOption>);
 
 /// Latex struct that contains everything related to a latex document 
 #[derive(Debug, Clone)]
@@ -14,13 +22,35 @@ pub struct Latex {
     /// - Class `(\documentclass{...})`
     /// - Fontsize `(\documentclass[..pt]{...})`
     /// - Papersize `(\documentclass[..pt, ...]{...})`
+    /// - Other options `(\documentclass[..pt, ..., ...]{...})`
+    ///
+    /// Options that are not set are not written
     pub document_class: Doc,
     /// Metadata contains the author, title and date 
     pub metadata: Metadata,
-    /// Packages contains all the packages `(\usepackage{...})`
+    /// Packages contains all the packages `(\usepackage[...]{...})`, the ones
+    /// the elements need are added when writing
     pub packages: Vec<Package>,
     /// Document elements contains a vector of all the elements
     pub document_elements: Vec<Element>,
+    /// Bibliography written next to the document and printed at its end
+    pub bibliography: Option<Bibliography>,
+    /// Title, abstract and lists written before the elements
+    pub front_matter: FrontMatter,
+    /// Beamer theme `(\usetheme{...})`
+    pub theme: Option<String>,
+    /// Beamer color theme `(\usecolortheme{...})`
+    pub color_theme: Option<String>,
+    /// Colors defined by name in the preamble `(\definecolor{...})`
+    pub colors: Vec<(String, Color)>,
+    /// Geometry, headers and footers, page numbering and line spacing
+    pub layout: PageLayout,
+    /// Link colors, bookmarks and PDF properties `(\hypersetup{...})`
+    pub hyperref: Option<Hyperref>,
+    /// Checks the labels and references when writing, on by default
+    pub check_references: bool,
+    /// Searches the written document for the packages raw LaTeX needs, off by default
+    pub scan_raw_packages: bool,
 }
 
 /// Different kind of document classes 
@@ -34,25 +64,118 @@ pub enum Class {
     Report,
     /// `\documentclass{beamer}`
     Beamer,
+    /// `\documentclass{memoir}`
+    Memoir,
+    /// `\documentclass{scrartcl}`
+    Scrartcl,
+    /// `\documentclass{scrbook}`
+    Scrbook,
+    /// `\documentclass{letter}`
+    Letter,
+    /// `\documentclass{exam}`
+    Exam,
+    /// `\documentclass{standalone}`
+    Standalone,
+    /// Any other class, like a local `thesis.cls` `(\documentclass{thesis})`
+    Custom(String),
 }
-/// The metadata in a latex document 
+/// Document class options
+/// - OneColumn `(onecolumn)`
+/// - TwoColumn `(twocolumn)`
+/// - OneSide `(oneside)`
+/// - TwoSide `(twoside)`
+/// - Draft `(draft)`
+/// - Final `(final)`
+/// - Landscape `(landscape)`
+/// - OpenAny, chapters start on any page `(openany)`
+/// - OpenRight, chapters start on right pages `(openright)`
+/// - TitlePage `(titlepage)`
+/// - NoTitlePage `(notitlepage)`
+/// - Leqno, equation numbers on the left `(leqno)`
+/// - Fleqn, equations aligned left `(fleqn)`
+/// - Custom option, like `12pt` for `exam`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassOption {
+    OneColumn,
+    TwoColumn,
+    OneSide,
+    TwoSide,
+    Draft,
+    Final,
+    Landscape,
+    OpenAny,
+    OpenRight,
+    TitlePage,
+    NoTitlePage,
+    Leqno,
+    Fleqn,
+    Custom(String),
+}
+/// The metadata in a latex document, fields hold LaTeX so use `Metadata::new` to escape them,
+/// fields that are not set are not written so LaTeX's defaults are used, like `\today`
 #[derive(Debug, Clone)]
 pub struct Metadata {
     /// `\author{...}`
-    pub author: String,
+    pub author: Option<String>,
     /// `\title{...}`
-    pub title: String,
+    pub title: Option<String>,
     /// `\date{...}`
-    pub date: String,
+    pub date: Option<String>,
 }
-/// Package tuple struct 
+/// What is written at the start of the document, before the elements
 #[derive(Debug, Clone)]
-pub struct Package(pub String);
+pub struct FrontMatter {
+    /// `\maketitle`, a title frame for beamer, skipped for classes without it like letter
+    pub title: bool,
+    /// `\begin{abstract}...`, already escaped, skipped for classes without it like book and beamer
+    pub abstract_text: Option<String>,
+    /// `\tableofcontents`, in its own frame for beamer
+    pub table_of_contents: bool,
+    /// `\listoffigures`
+    pub list_of_figures: bool,
+    /// `\listoftables`
+    pub list_of_tables: bool,
+    /// `\newpage` after the front matter, not written for beamer
+    pub new_page: bool,
+    /// `\frontmatter` before and `\mainmatter` after the front matter, book only
+    pub book_matter: bool,
+}
+/// Package tuple struct with the name and options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package(pub String, pub Vec<String>);
 
 // Implementations //
 impl Package {
     pub fn new(pkg: &str) -> Self {
-        Package(pkg.to_string())
+        Package(pkg.to_string(), Vec::new())
+    }
+    /// Creates a package with options, like `utf8` for `inputenc`
+    pub fn with_options(pkg: &str, options: &[&str]) -> Self {
+        let mut p = Self::new(pkg);
+        for o in options {
+            p.add_option(o);
+        }
+        p
+    }
+    /// Adds an option, options already given are skipped
+    pub fn add_option(&mut self, option: &str) {
+        if !self.1.iter().any(|o| o == option) {
+            self.1.push(option.to_string());
+        }
+    }
+    /// Where the package goes in the preamble, packages are kept in the order
+    /// they were added within each place
+    /// - 0: encodings `(inputenc, fontenc)`
+    /// - 1: everything else
+    /// - 2: `hyperref`, which should be loaded late
+    /// - 3: packages that must come after `hyperref` `(cleveref, bookmark, hypcap, glossaries)`
+    pub fn load_order(&self) -> u8 {
+        match self.0.as_str() {
+            "inputenc" | "fontenc" => 0,
+            "hyperref" => 2,
+            "cleveref" | "bookmark" | "hypcap" | "glossaries" => 3,
+            _ => 1,
+        }
     }
 }
 
@@ -63,37 +186,180 @@ impl Class {
             Class::Book => "book".to_string(),
             Class::Report => "report".to_string(),
             Class::Beamer => "beamer".to_string(),
+            Class::Memoir => "memoir".to_string(),
+            Class::Scrartcl => "scrartcl".to_string(),
+            Class::Scrbook => "scrbook".to_string(),
+            Class::Letter => "letter".to_string(),
+            Class::Exam => "exam".to_string(),
+            Class::Standalone => "standalone".to_string(),
+            Class::Custom(c) => c.clone(),
+        }
+    }
+    /// Class from its name, unknown names are `Custom`
+    pub fn from_class_str(class: &str) -> Self {
+        match class {
+            "article" => Class::Article,
+            "book" => Class::Book,
+            "report" => Class::Report,
+            "beamer" => Class::Beamer,
+            "memoir" => Class::Memoir,
+            "scrartcl" => Class::Scrartcl,
+            "scrbook" => Class::Scrbook,
+            "letter" => Class::Letter,
+            "exam" => Class::Exam,
+            "standalone" => Class::Standalone,
+            c => Class::Custom(c.to_string()),
+        }
+    }
+    /// Checks if the class has `\frontmatter` and `\mainmatter`
+    pub fn has_book_matter(&self) -> bool {
+        matches!(self, Class::Book | Class::Memoir | Class::Scrbook)
+    }
+    /// Checks if the class has a title, `\maketitle` or a beamer title page,
+    /// custom classes are expected to have it
+    pub fn has_title(&self) -> bool {
+        !matches!(self, Class::Letter | Class::Standalone)
+    }
+    /// Checks if the class has the `abstract` environment, custom classes are
+    /// expected to have it
+    pub fn has_abstract(&self) -> bool {
+        matches!(
+            self,
+            Class::Article | Class::Report | Class::Memoir | Class::Scrartcl | Class::Custom(_)
+        )
+    }
+}
+
+impl ClassOption {
+    /// Option from how it is written, unknown options are `Custom`
+    pub fn from_option_str(option: &str) -> Self {
+        match option {
+            "onecolumn" => ClassOption::OneColumn,
+            "twocolumn" => ClassOption::TwoColumn,
+            "oneside" => ClassOption::OneSide,
+            "twoside" => ClassOption::TwoSide,
+            "draft" => ClassOption::Draft,
+            "final" => ClassOption::Final,
+            "landscape" => ClassOption::Landscape,
+            "openany" => ClassOption::OpenAny,
+            "openright" => ClassOption::OpenRight,
+            "titlepage" => ClassOption::TitlePage,
+            "notitlepage" => ClassOption::NoTitlePage,
+            "leqno" => ClassOption::Leqno,
+            "fleqn" => ClassOption::Fleqn,
+            o => ClassOption::Custom(o.to_string()),
         }
     }
 }
 
 impl Metadata {
-    pub fn new(title: &str, author: &str, date: &str) -> Self {
+    /// Creates metadata, escaping each field unless it is `Raw`
+    pub fn new(title: impl Convert, author: impl Convert, date: impl Convert) -> Self {
+        Self {
+            title: Some(title.to_latex_string()),
+            author: Some(author.to_latex_string()),
+            date: Some(date.to_latex_string()),
+        }
+    }
+    /// Metadata without any field set
+    pub fn none() -> Self {
+        Self {
+            author: None,
+            title: None,
+            date: None,
+        }
+    }
+}
+
+impl FrontMatter {
+    /// Title followed by a new page
+    pub fn new() -> Self {
+        Self {
+            title: true,
+            new_page: true,
+            ..Self::none()
+        }
+    }
+    /// Nothing before the elements
+    pub fn none() -> Self {
         Self {
-            title: title.to_string(),
-            author: author.to_string(),
-            date: date.to_string(),
+            title: false,
+            abstract_text: None,
+            table_of_contents: false,
+            list_of_figures: false,
+            list_of_tables: false,
+            new_page: false,
+            book_matter: false,
         }
     }
+    /// Sets if the title is written
+    pub fn set_title(&mut self, title: bool) {
+        self.title = title;
+    }
+    /// Sets the abstract, escaping it unless it is `Raw`
+    pub fn set_abstract(&mut self, text: impl Convert) {
+        self.abstract_text = Some(text.to_latex_string());
+    }
+    /// Sets if the table of contents is written
+    pub fn set_table_of_contents(&mut self, toc: bool) {
+        self.table_of_contents = toc;
+    }
+    /// Sets if the list of figures is written
+    pub fn set_list_of_figures(&mut self, lof: bool) {
+        self.list_of_figures = lof;
+    }
+    /// Sets if the list of tables is written
+    pub fn set_list_of_tables(&mut self, lot: bool) {
+        self.list_of_tables = lot;
+    }
+    /// Sets if a new page follows the front matter
+    pub fn set_new_page(&mut self, new_page: bool) {
+        self.new_page = new_page;
+    }
+    /// Sets if `\frontmatter` and `\mainmatter` are written for books
+    pub fn set_book_matter(&mut self, book_matter: bool) {
+        self.book_matter = book_matter;
+    }
+}
+
+impl Default for FrontMatter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Default for Metadata {
     fn default() -> Self {
         Self {
-            author: "default author".to_string(),
-            title: "default title".to_string(),
-            date: "what day is it?".to_string(),
+            author: Some("default author".to_string()),
+            title: Some("default title".to_string()),
+            date: Some("what day is it?".to_string()),
         }
     }
 }
 
+impl Default for Latex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Latex {
     pub fn new() -> Self {
         Self {
-            document_class: (Class::Article,Some(11), Some("letterpaper".to_string())),
+            document_class: (Class::Article, Some(11), Some("letterpaper".to_string()), Vec::new()),
             metadata: Metadata::default(),
             packages: Vec::new(),
             document_elements: Vec::new(),
+            bibliography: None,
+            front_matter: FrontMatter::default(),
+            theme: None,
+            color_theme: None,
+            colors: Vec::new(),
+            layout: PageLayout::default(),
+            hyperref: None,
+            check_references: true,
+            scan_raw_packages: false,
         }
     }
     /// Sets the class for Latex Document 
@@ -105,6 +371,32 @@ impl Latex {
         self.document_class.1 = Some(font_size);
         self.document_class.2 = Some(paper_size.to_string());
     }
+    /// Sets the page layout
+    pub fn set_layout(&mut self, layout: PageLayout) {
+        self.layout = layout;
+    }
+    /// Sets up `hyperref`, loading it when the class doesn't, the PDF title
+    /// and author come from `\title` and `\author` unless they are set
+    pub fn set_hyperref(&mut self, hyperref: Hyperref) {
+        self.hyperref = Some(hyperref);
+    }
+    /// Sets if the labels and references are checked when writing,
+    /// `validate` always checks them
+    pub fn set_check_references(&mut self, check: bool) {
+        self.check_references = check;
+    }
+    /// Sets if the packages needed by raw LaTeX, like `Raw` text and `UserDefined`
+    /// commands, are found by searching the written document for the commands
+    /// and environments that need them, typed parts always add their packages
+    pub fn set_scan_raw_packages(&mut self, scan: bool) {
+        self.scan_raw_packages = scan;
+    }
+    /// Adds a class option, like `TwoColumn`
+    pub fn add_class_option(&mut self, option: ClassOption) {
+        if !self.document_class.3.contains(&option) {
+            self.document_class.3.push(option);
+        }
+    }
     /// Sets the metadata for the Latex Document 
     pub fn set_metadata(&mut self, meta: Metadata) {
         self.metadata = meta;
@@ -115,12 +407,130 @@ impl Latex {
     }
     /// Adds a single package to the packages 
     pub fn add_package(&mut self, package: String) {
-        self.packages.push(Package(package));
+        self.use_package(Package::new(&package));
+    }
+    /// Adds a package with its options, the options of a package that is
+    /// already added are merged into it
+    pub fn use_package(&mut self, package: Package) {
+        match self.packages.iter_mut().find(|p| p.0 == package.0) {
+            Some(p) => package.1.iter().for_each(|o| p.add_option(o)),
+            None => self.packages.push(package),
+        }
     }
     /// Sets the elements for the Latex Document
     pub fn set_elements(&mut self, elements: &Vec<Element>) {
         self.document_elements = elements.to_owned();
     }
+    /// Sets the bibliography for the Latex Document
+    pub fn set_bibliography(&mut self, bibliography: Bibliography) {
+        self.bibliography = Some(bibliography);
+    }
+    /// Sets what is written before the elements
+    pub fn set_front_matter(&mut self, front_matter: FrontMatter) {
+        self.front_matter = front_matter;
+    }
+    /// Sets the beamer theme, like `Madrid`
+    pub fn set_theme(&mut self, theme: &str) {
+        self.theme = Some(theme.to_string());
+    }
+    /// Sets the beamer color theme, like `beaver`
+    pub fn set_color_theme(&mut self, color_theme: &str) {
+        self.color_theme = Some(color_theme.to_string());
+    }
+    /// Defines a color by name in the preamble so styles can use it as
+    /// `Color::Named`, a color with the same name is replaced
+    pub fn define_color(&mut self, name: &str, color: Color) {
+        self.colors.retain(|(n, _)| n != name);
+        self.colors.push((name.to_string(), color));
+    }
+    /// Keys cited by the elements that are not in the bibliography,
+    /// these are warned about when compiling as undefined citations
+    pub fn missing_citations(&self) -> Vec<String> {
+        let mut out = Output::new();
+        self.body(&mut out, None);
+        check_citations(&out.lines.join("\n"), self.bibliography.as_ref())
+            .into_iter()
+            .filter_map(|p| match p {
+                CitationProblem::Missing(k) => Some(k),
+                CitationProblem::NeedsBiblatex(_) => None,
+            })
+            .collect()
+    }
+    /// Packages needed by the elements and defined colors that are not in packages yet,
+    /// and by the raw LaTeX when `scan_raw_packages` is on
+    /// With the biblatex backend `natbib` is replaced by biblatex's `natbib=true`
+    /// option, loading both is an error
+    pub fn missing_packages(&self) -> Vec<Package> {
+        let mut v: Vec<Package> = Vec::new();
+        let biblatex = matches!(
+            self.bibliography.as_ref().map(|b| &b.backend),
+            Some(BibBackend::Biblatex(_))
+        );
+        let colors = self.colors.first().map(|_| "xcolor".to_string());
+        let elements = self.document_elements.iter().flat_map(|e| e.packages());
+        let raw = match self.scan_raw_packages {
+            true => {
+                let mut out = Output::new();
+                self.head(&mut out);
+                self.body(&mut out, None);
+                raw_packages(&out.lines.join("\n"))
+            }
+            false => Vec::new(),
+        };
+        for pkg in colors.into_iter().chain(elements).chain(raw) {
+            let pkg = match pkg.as_str() {
+                "natbib" if biblatex => Package::with_options("biblatex", &["natbib=true"]),
+                _ => Package::new(&pkg),
+            };
+            let added = self.packages.iter().chain(&v).any(|p| p.0 == pkg.0);
+            if !added {
+                v.push(pkg);
+            }
+        }
+        v
+    }
+    /// Packages as written in the preamble, the added ones and the ones the
+    /// layout, bibliography, `hyperref` setup and elements need, each once with
+    /// the options of every time it was added, in load order
+    pub fn resolved_packages(&self) -> Vec<Package> {
+        let mut v: Vec<Package> = Vec::new();
+        // Beamer loads hyperref itself
+        let hyperref = match (&self.hyperref, &self.document_class.0) {
+            (Some(_), Class::Beamer) | (None, _) => None,
+            (Some(h), _) => {
+                let options = h.package_options();
+                let options: Vec<&str> = options.iter().map(|o| o.as_str()).collect();
+                Some(Package::with_options("hyperref", &options))
+            }
+        };
+        let bibliography = self.bibliography.as_ref().and_then(|b| b.package());
+        let added = self.packages.iter().cloned().chain(self.layout.packages());
+        let added = added.chain(bibliography);
+        for pkg in added.chain(hyperref).chain(self.missing_packages()) {
+            match v.iter_mut().find(|p| p.0 == pkg.0) {
+                Some(p) => pkg.1.iter().for_each(|o| p.add_option(o)),
+                None => v.push(pkg),
+            }
+        }
+        v.sort_by_key(|p| p.load_order());
+        v
+    }
+    /// Files the elements refer to, like images
+    pub fn assets(&self) -> Vec<PathBuf> {
+        let mut v: Vec<PathBuf> = Vec::new();
+        for asset in self.document_elements.iter().flat_map(|e| e.assets()) {
+            if !v.contains(&asset) {
+                v.push(asset);
+            }
+        }
+        v
+    }
+    /// Files of the `Input` elements, with `.tex` added when there is no extension
+    pub fn inputs(&self) -> Vec<PathBuf> {
+        let mut v = Vec::new();
+        input_files(&self.document_elements, &mut v);
+        v
+    }
     /// Returns a vector of UDTuple, used for write so they 
     /// can be written in the correct location 
     pub fn get_ud(&self) -> Vec<UDTuple>{
@@ -138,142 +548,115 @@ impl Latex {
         }
         v
     }
+    /// Checks that every element can be written, like a figure having images,
+    /// that every reference has a label and that no label is defined
+    /// twice or on an unnumbered element, labels in `\input` files are read
+    /// relative to the current directory, then that every citation is in the
+    /// bibliography and defined by its backend
+    pub fn validate(&self) -> Result<(), TexError> {
+        let mut out = Output::new();
+        self.head(&mut out);
+        self.package_lines(&mut out);
+        self.body(&mut out, None);
+        let content = out.lines.join("\n");
+        self.check_elements()?;
+        self.check(&content, Path::new(""))?;
+        let problems = check_citations(&content, self.bibliography.as_ref());
+        match problems.is_empty() {
+            true => Ok(()),
+            false => Err(TexError::CitationError(problems)),
+        }
+    }
     /// Normal write that uses `std`
-    pub fn write(&self, path: PathBuf) -> Result<(), std::io::Error> {
-        let ud_vec = &self.get_ud();
+    /// Returns a `ReferenceError` when a reference is broken and nothing is written,
+    /// unless the check is turned off with `set_check_references`, and a
+    /// `DataError` when an element can't be written, like a figure without images
+    pub fn write(&self, path: PathBuf) -> Result<(), TexError> {
+        self.write_with_source_map(path).map(|_| ())
+    }
+    /// Normal write that also returns the source map of the written lines
+    pub fn write_with_source_map(&self, path: PathBuf) -> Result<SourceMap, TexError> {
         let path = path.as_path();
-        let mut file = std::fs::File::create(path)?;
-        let mut s: Vec<String> = Vec::new();
-        s.push(self.document_class.to_latex_string());
-        s.push(self.metadata.to_latex_string());
-        for i in ud_vec{
-            s.push(i.1.to_owned())
+        let mut out = Output::new();
+        self.head(&mut out);
+        self.package_lines(&mut out);
+        self.body(&mut out, None);
+        let content = out.lines.join("\n");
+        self.check_elements()?;
+        if self.check_references {
+            self.check(&content, path.parent().unwrap_or(Path::new("")))?;
         }
-        for i in &self.packages {
-            s.push(i.to_latex_string())
+        std::fs::File::create(path)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+            .map_err(|e| TexError::io(path, e))?;
+        if let Some((bib, bib_content)) = self.bib_file(path.parent().unwrap_or(Path::new(""))) {
+            std::fs::write(&bib, bib_content).map_err(|e| TexError::io(&bib, e))?;
         }
-        for i in ud_vec{
-            s.push(i.2.to_owned())
+        Ok(out.map)
+    }
+    /// Normal write that first checks that every asset exists, returning
+    /// `MissingAssets` if not, and with `copy` copies relative assets and the
+    /// files of `Input` elements that exist next to the output
+    /// Files are copied to the same relative path so the document reads the same,
+    /// files that are absolute or outside the current directory are not copied
+    pub fn write_with_assets(&self, path: PathBuf, copy: bool) -> Result<(), TexError> {
+        let assets = self.assets();
+        let missing: Vec<PathBuf> = assets.iter().filter(|a| !a.is_file()).cloned().collect();
+        if !missing.is_empty() {
+            return Err(TexError::MissingAssets(missing));
         }
-        s.push(String::from("\\begin{document}"));
-        s.push(String::from("\\maketitle\n\\newpage"));
-        // By attach things should be order by priority
-        for i in &self.document_elements {
-            match i {
-                Element::Part(e) => {
-                    s.push(e.to_latex_string());
-                    for j in &e.1 {
-                        s.push(j.to_latex_string());
-                        s.push(j.loop_through())
+        if copy {
+            let dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+            let inputs = self.inputs().into_iter().filter(|i| i.is_file());
+            let files: Vec<PathBuf> = assets.into_iter().chain(inputs).collect();
+            for file in &files {
+                let inside = file
+                    .components()
+                    .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+                let target = dir.join(file);
+                if inside && target != *file {
+                    if let Some(parent) = target.parent() {
+                        std::fs::create_dir_all(parent).map_err(|e| TexError::io(parent, e))?;
                     }
+                    std::fs::copy(file, &target).map_err(|e| TexError::io(&target, e))?;
                 }
-                Element::Chapter(e) => {
-                    s.push(e.to_latex_string());
-                    for j in &e.1 {
-                        s.push(j.to_latex_string());
-                        s.push(j.loop_through())
-                    }
-                }
-                Element::Section(e) => {
-                    s.push(e.to_latex_string());
-                    for j in &e.1 {
-                        s.push(j.to_latex_string());
-                        s.push(j.loop_through())
-                    }
-                }
-                Element::Paragraph(e) => {
-                    s.push(e.to_latex_string());
-                    for j in e.1.as_ref().unwrap() {
-                        s.push(j.to_latex_string());
-                        s.push(j.loop_through())
-                    }
-                }
-                Element::Environment(e) => {
-                    s.push(e.to_latex_string());
-                }
-                Element::List(e) => s.push(e.to_latex_string()),
-                Element::UserDefined(e) => {
-                    let ud = e.evaluate();
-                    s.push(ud.0)
-                },
-                Element::Text(e) => s.push(e.to_latex_string()),
-                Element::Input(e) => s.push(e.to_latex_string()),
             }
         }
-        s.push(String::from("\\end{document}"));
-        let content = s.join("\n");
-        file.write_all(&content.as_bytes())?;
-        Ok(())
+        self.write(path)
+    }
+    /// Source map of the lines `write` would produce, without writing
+    pub fn source_map(&self) -> SourceMap {
+        let mut out = Output::new();
+        self.head(&mut out);
+        self.package_lines(&mut out);
+        self.body(&mut out, None);
+        out.map
+    }
+    /// Compiles the document to PDF with a local TeX engine, see `Compiler`
+    /// Returns the path of the PDF
+    pub fn compile(&self, compiler: &Compiler) -> Result<PathBuf, TexError> {
+        compiler.compile(self)
     }
     /// Asynchronous and parallel write using `async_std` and `rayon`
-    pub async fn async_write(&self, path: PathBuf) -> Result<(), async_std::io::Error> {
-        let ud_vec = &self.get_ud();
+    pub async fn async_write(&self, path: PathBuf) -> Result<(), TexError> {
         let pool = rayon::ThreadPoolBuilder::new()
             .num_threads(4)
             .build()
             .unwrap();
         let path = path.as_path();
-        let mut file = File::create(path).await?;
-        let mut s: Vec<String> = Vec::new();
-        s.push(self.document_class.to_latex_string());
-        s.push(self.metadata.to_latex_string());
-        for i in ud_vec{
-            s.push(i.1.to_owned())
-        }
-        for i in &self.packages {
-            s.push(i.to_latex_string())
+        let mut out = Output::new();
+        self.head(&mut out);
+        self.package_lines(&mut out);
+        self.body(&mut out, Some(&pool));
+        let content = out.lines.join("\n");
+        self.check_elements()?;
+        if self.check_references {
+            self.check(&content, path.parent().unwrap_or(Path::new("")))?;
         }
-        for i in ud_vec{
-            s.push(i.2.to_owned())
+        write_file(path, &content).await?;
+        if let Some((bib, bib_content)) = self.bib_file(path.parent().unwrap_or(Path::new(""))) {
+            write_file(&bib, &bib_content).await?;
         }
-        s.push(String::from("\\begin{document}"));
-        s.push(String::from("\\maketitle\n\\newpage"));
-        // By attach things should be order by priority
-        for i in &self.document_elements {
-            match i {
-                Element::Part(e) => {
-                    s.push(e.to_latex_string());
-                    for j in &e.1 {
-                        s.push(j.to_latex_string());
-                        s.push(pool.install(|| j.clone().loop_through_parallel()))
-                    }
-                }
-                Element::Chapter(e) => {
-                    s.push(e.to_latex_string());
-                    for j in &e.1 {
-                        s.push(j.to_latex_string());
-                        s.push(pool.install(|| j.clone().loop_through_parallel()))
-                    }
-                }
-                Element::Section(e) => {
-                    s.push(e.to_latex_string());
-                    for j in &e.1 {
-                        s.push(j.to_latex_string());
-                        s.push(pool.install(|| j.clone().loop_through_parallel()))
-                    }
-                }
-                Element::Paragraph(e) => {
-                    s.push(e.to_latex_string());
-                    for j in e.1.as_ref().unwrap() {
-                        s.push(j.to_latex_string());
-                        s.push(pool.install(|| j.clone().loop_through_parallel()))
-                    }
-                }
-                Element::Environment(e) => {
-                    s.push(e.to_latex_string());
-                }
-                Element::List(e) => s.push(e.to_latex_string()),
-                Element::UserDefined(e) => {
-                    let ud = e.evaluate();
-                    s.push(ud.0)
-                },
-                Element::Text(e) => s.push(e.to_latex_string()),
-                Element::Input(e) => s.push(e.to_latex_string()),
-            }
-        }
-        s.push(String::from("\\end{document}"));
-        let content = s.join("\n");
-        file.write_all(&content.as_bytes()).await?;
         Ok(())
     }
     /// Split write that writes a `main` and `structure` file 
@@ -282,110 +665,327 @@ impl Latex {
         &self,
         main: PathBuf,
         structure: PathBuf,
-    ) -> Result<(), async_std::io::Error> {
-        let ud_vec = &self.get_ud();
+    ) -> Result<(), TexError> {
         let main = main.as_path();
         let structure = structure.as_path();
 
-        let mut s: Vec<String> = Vec::new();
-        let mut struct_s: Vec<String> = Vec::new();
+        let mut out = Output::new();
+        let mut struct_out = Output::new();
+        self.head(&mut out);
+        self.package_lines(&mut struct_out);
+        self.body(&mut out, None);
 
-        s.push(self.document_class.to_latex_string());
-        s.push(self.metadata.to_latex_string());
-        for i in ud_vec{
-            s.push(i.1.to_owned())
+        let content = out.lines.join("\n");
+        let structure_cont = struct_out.lines.join("\n");
+        let both = format!("{}\n{}", structure_cont, content);
+        self.check_elements()?;
+        if self.check_references {
+            self.check(&both, main.parent().unwrap_or(Path::new("")))?;
         }
-        for i in &self.packages {
-            struct_s.push(i.to_latex_string())
+
+        write_file(main, &content).await?;
+        write_file(structure, &structure_cont).await?;
+        if let Some((bib, bib_content)) = self.bib_file(main.parent().unwrap_or(Path::new(""))) {
+            write_file(&bib, &bib_content).await?;
         }
-        for i in ud_vec{
-            struct_s.push(i.2.to_owned())
+        Ok(())
+    }
+    /// Checks that every element can be written, see `Element::check`
+    fn check_elements(&self) -> Result<(), TexError> {
+        self.document_elements.iter().try_for_each(|e| e.check())
+    }
+    /// Checks the references of written content, with the labels of the
+    /// `\input` files found relative to `dir`, and that no label is on an
+    /// unnumbered element
+    fn check(&self, content: &str, dir: &Path) -> Result<(), TexError> {
+        let mut extra = Vec::new();
+        input_labels(&self.inputs(), dir, &mut extra);
+        let mut problems: Vec<ReferenceProblem> = self
+            .document_elements
+            .iter()
+            .flat_map(|e| e.unnumbered_labels())
+            .map(ReferenceProblem::Unnumbered)
+            .collect();
+        problems.extend(check_references(content, &extra));
+        match problems.is_empty() {
+            true => Ok(()),
+            false => Err(TexError::ReferenceError(problems)),
         }
-        s.push(String::from("\\begin{document}"));
-        s.push(String::from("\\maketitle\n\\newpage"));
-        // By attach things should be order by priority
-        for i in &self.document_elements {
-            match i {
-                Element::Part(e) => {
-                    s.push(e.to_latex_string());
-                    for j in &e.1 {
-                        s.push(j.to_latex_string());
-                        s.push(j.loop_through())
-                    }
-                }
-                Element::Chapter(e) => {
-                    s.push(e.to_latex_string());
-                    for j in &e.1 {
-                        s.push(j.to_latex_string());
-                        s.push(j.loop_through())
-                    }
+    }
+    /// Document class, metadata and the meta level user-defined commands
+    fn head(&self, out: &mut Output) {
+        out.push(self.document_class.to_latex_string());
+        out.push(self.metadata.to_latex_string());
+        for (i, e) in self.document_elements.iter().enumerate() {
+            match e {
+                Element::UserDefined(u) => {
+                    out.push_mapped(u.evaluate().1, ElementPath::root(e, i))
                 }
-                Element::Section(e) => {
-                    s.push(e.to_latex_string());
-                    for j in &e.1 {
-                        s.push(j.to_latex_string());
-                        s.push(j.loop_through())
-                    }
+                _ => out.push(String::new()),
+            }
+        }
+    }
+    /// Packages, the ones the elements need and the package level
+    /// user-defined commands
+    fn package_lines(&self, out: &mut Output) {
+        for i in &self.resolved_packages() {
+            out.push(i.to_latex_string())
+        }
+        if let Some(b) = self.bibliography.as_ref().filter(|b| !b.preamble().is_empty()) {
+            out.push(b.preamble())
+        }
+        if let Some(t) = &self.theme {
+            out.push(format!("\\usetheme{{{}}}", t))
+        }
+        if let Some(t) = &self.color_theme {
+            out.push(format!("\\usecolortheme{{{}}}", t))
+        }
+        for (name, color) in &self.colors {
+            out.push(color.definition(name))
+        }
+        for i in self.layout.preamble() {
+            out.push(i)
+        }
+        if let Some(h) = &self.hyperref {
+            out.push(h.setup())
+        }
+        for (i, e) in self.document_elements.iter().enumerate() {
+            match e {
+                Element::UserDefined(u) => {
+                    out.push_mapped(u.evaluate().2, ElementPath::root(e, i))
                 }
-                Element::Paragraph(e) => {
-                    s.push(e.to_latex_string());
-                    for j in e.1.as_ref().unwrap() {
-                        s.push(j.to_latex_string());
-                        s.push(j.loop_through())
+                _ => out.push(String::new()),
+            }
+        }
+    }
+    /// The document environment, children of the top level elements are
+    /// converted in parallel when given a pool
+    fn body(&self, out: &mut Output, pool: Option<&rayon::ThreadPool>) {
+        out.push(String::from("\\begin{document}"));
+        let front = self.front_lines();
+        if !front.is_empty() {
+            out.push(front.join("\n"));
+        }
+        // By attach things should be order by priority
+        for (i, e) in self.document_elements.iter().enumerate() {
+            let path = ElementPath::root(e, i);
+            match e {
+                Element::Part(_)
+                | Element::Chapter(_)
+                | Element::Section(_)
+                | Element::Subsection(_)
+                | Element::Subsubsection(_)
+                | Element::Paragraph(_)
+                | Element::Subparagraph(_) => {
+                    out.push_mapped(e.to_latex_string(), path.clone());
+                    for (k, j) in e.children().into_iter().flatten().enumerate() {
+                        let child = path.child(j, k);
+                        match pool {
+                            Some(pool) => {
+                                out.push_mapped(j.to_latex_string(), child.clone());
+                                out.push_mapped(pool.install(|| j.loop_through_parallel()), child);
+                            }
+                            None => out.push_element(j, child),
+                        }
                     }
                 }
-                Element::Environment(e) => {
-                    s.push(e.to_latex_string());
-                }
-                Element::List(e) => s.push(e.to_latex_string()),
-                Element::UserDefined(e) => {
-                    let ud = e.evaluate();
-                    s.push(ud.0)
-                },
-                Element::Text(e) => s.push(e.to_latex_string()),
-                Element::Input(e) => s.push(e.to_latex_string()),
+                Element::UserDefined(u) => out.push_mapped(u.evaluate().0, path),
+                _ => out.push_mapped(e.to_latex_string(), path),
+            }
+        }
+        if let Some(b) = &self.bibliography {
+            out.push(b.to_latex_string())
+        }
+        out.push(String::from("\\end{document}"));
+    }
+    /// Lines of the front matter, for the class of the document
+    fn front_lines(&self) -> Vec<String> {
+        let f = &self.front_matter;
+        let beamer = matches!(self.document_class.0, Class::Beamer);
+        let book = f.book_matter && self.document_class.0.has_book_matter();
+        // Beamer only writes things inside frames
+        let frame = |s: &str| match beamer {
+            true => format!("\\begin{{frame}}\n{}\n\\end{{frame}}", s),
+            false => s.to_string(),
+        };
+        let mut v = Vec::new();
+        if book {
+            v.push("\\frontmatter".to_string());
+        }
+        if f.title && self.document_class.0.has_title() {
+            match beamer {
+                true => v.push(frame("\\titlepage")),
+                false => v.push("\\maketitle".to_string()),
             }
         }
-        s.push(String::from("\\end{document}"));
-        let content = s.join("\n");
-        let mut main_file = File::create(main).await?;
-        main_file.write_all(&content.as_bytes()).await?;
+        if let Some(a) = f.abstract_text.as_ref().filter(|_| self.document_class.0.has_abstract()) {
+            v.push(format!("\\begin{{abstract}}\n{}\n\\end{{abstract}}", a));
+        }
+        if f.table_of_contents {
+            v.push(frame("\\tableofcontents"));
+        }
+        if f.list_of_figures {
+            v.push(frame("\\listoffigures"));
+        }
+        if f.list_of_tables {
+            v.push(frame("\\listoftables"));
+        }
+        if f.new_page && !beamer {
+            v.push("\\newpage".to_string());
+        }
+        if book {
+            v.push("\\mainmatter".to_string());
+        }
+        v
+    }
+    /// Path and contents of the `.bib` file written next to a document in `dir`
+    fn bib_file(&self, dir: &Path) -> Option<(PathBuf, String)> {
+        self.bibliography
+            .as_ref()
+            .map(|b| (dir.join(b.file_name()), b.to_bib_string()))
+    }
+}
 
-        let structure_cont = struct_s.join("\n");
-        let mut struct_file = File::create(structure).await?;
-        struct_file.write_all(&structure_cont.as_bytes()).await?;
-        Ok(())
+/// Files of the `Input` elements and the ones in their children, in order
+fn input_files(elements: &[Element], v: &mut Vec<PathBuf>) {
+    for e in elements {
+        if let Element::Input(i) = e {
+            let mut file = PathBuf::from(&i.0);
+            if file.extension().is_none() {
+                file.set_extension("tex");
+            }
+            if !v.contains(&file) {
+                v.push(file);
+            }
+        }
+        input_files(e.children().map(|c| c.as_slice()).unwrap_or(&[]), v);
+    }
+}
+
+/// Labels defined in the input files read from `dir`, files that can't be read are skipped
+fn input_labels(inputs: &[PathBuf], dir: &Path, v: &mut Vec<String>) {
+    for file in inputs {
+        if let Ok(source) = std::fs::read_to_string(dir.join(file)) {
+            v.extend(labels(&source));
+        }
+    }
+}
+
+/// Creates a file with `async_std` and writes the content, errors keep the path
+async fn write_file(path: &Path, content: &str) -> Result<(), TexError> {
+    let mut file = File::create(path).await.map_err(|e| TexError::io(path, e))?;
+    file.write_all(content.as_bytes())
+        .await
+        .map_err(|e| TexError::io(path, e))
+}
+
+/// Lines of a document being written, with the source map of the elements
+/// that wrote them
+struct Output {
+    lines: Vec<String>,
+    next_line: usize,
+    map: SourceMap,
+}
+
+impl Output {
+    fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            next_line: 1,
+            map: SourceMap::default(),
+        }
+    }
+    fn push(&mut self, s: String) {
+        self.next_line += s.matches('\n').count() + 1;
+        self.lines.push(s);
+    }
+    /// Pushes a string written by the element at `path`, empty strings are not mapped
+    fn push_mapped(&mut self, s: String, path: ElementPath) {
+        let start = self.next_line;
+        let empty = s.is_empty();
+        self.push(s);
+        if !empty {
+            self.map.add(start, self.next_line - 1, path);
+        }
+    }
+    /// Pushes an element and its children, giving the same lines as
+    /// `to_latex_string` followed by `loop_through`
+    fn push_element(&mut self, element: &Element, path: ElementPath) {
+        self.push_mapped(element.to_latex_string(), path.clone());
+        match element.children() {
+            Some(children) if !children.is_empty() => {
+                for (i, child) in children.iter().enumerate() {
+                    self.push_element(child, path.child(child, i));
+                }
+            }
+            _ => self.push(String::new()),
+        }
     }
 }
 
 // Trait Implementations
 impl Convert for Doc{
     fn to_latex_string(&self) -> String {
-        let fs = match self.1.clone(){
-            Some(f) => f.to_string(), 
-            None => "11".to_owned()
-        };
-        let paper = match self.2.clone(){
-            Some(a) => a,
-            None => "letterpaper".to_owned() 
-        };
-        format!("\\documentclass[{}pt, {}]{{{}}}", &fs, &paper, &self.0.as_class_str())
+        let mut options = Vec::new();
+        if let Some(f) = self.1 {
+            options.push(format!("{}pt", f));
+        }
+        if let Some(p) = &self.2 {
+            options.push(p.clone());
+        }
+        options.extend(self.3.iter().map(|o| o.to_latex_string()));
+        match options.is_empty() {
+            true => format!("\\documentclass{{{}}}", &self.0.as_class_str()),
+            false => format!(
+                "\\documentclass[{}]{{{}}}",
+                options.join(", "),
+                &self.0.as_class_str()
+            ),
+        }
+    }
+}
+
+impl Convert for ClassOption {
+    fn to_latex_string(&self) -> String {
+        match self {
+            ClassOption::OneColumn => "onecolumn".to_string(),
+            ClassOption::TwoColumn => "twocolumn".to_string(),
+            ClassOption::OneSide => "oneside".to_string(),
+            ClassOption::TwoSide => "twoside".to_string(),
+            ClassOption::Draft => "draft".to_string(),
+            ClassOption::Final => "final".to_string(),
+            ClassOption::Landscape => "landscape".to_string(),
+            ClassOption::OpenAny => "openany".to_string(),
+            ClassOption::OpenRight => "openright".to_string(),
+            ClassOption::TitlePage => "titlepage".to_string(),
+            ClassOption::NoTitlePage => "notitlepage".to_string(),
+            ClassOption::Leqno => "leqno".to_string(),
+            ClassOption::Fleqn => "fleqn".to_string(),
+            ClassOption::Custom(o) => o.clone(),
+        }
     }
 }
 
 impl Convert for Metadata {
     fn to_latex_string(&self) -> String {
-        let v = vec![
-            format!("\\author{{{}}}", &self.author),
-            format!("\\title{{{}}}", &self.title),
-            format!("\\date{{{}}}", &self.date),
+        let fields = [
+            ("author", &self.author),
+            ("title", &self.title),
+            ("date", &self.date),
         ];
+        let v: Vec<String> = fields
+            .iter()
+            .filter_map(|(name, value)| Some(format!("\\{}{{{}}}", name, value.as_ref()?)))
+            .collect();
         v.join("\n")
     }
 }
 
 impl Convert for Package {
     fn to_latex_string(&self) -> String {
-        format!("\\usepackage{{{}}}", &self.0)
+        match self.1.is_empty() {
+            true => format!("\\usepackage{{{}}}", &self.0),
+            false => format!("\\usepackage[{}]{{{}}}", self.1.join(", "), &self.0),
+        }
     }
 }
@@ -0,0 +1,279 @@
+use crate::latex::Package;
+use crate::Convert;
+
+/// Page layout written in the preamble with the packages it needs
+#[derive(Debug, Clone, Default)]
+pub struct PageLayout {
+    /// Margins, paper and orientation `(geometry)`
+    pub geometry: Option<Geometry>,
+    /// Headers and footers of each page style `(fancyhdr)`
+    pub page_styles: Vec<PageStyle>,
+    /// Page style used for the document `(\pagestyle{...})`
+    pub page_style: Option<String>,
+    /// Page numbering style `(\pagenumbering{...})`
+    pub numbering: Option<PageNumbering>,
+    /// Line spacing `(setspace)`
+    pub spacing: Option<LineSpacing>,
+}
+
+/// Page geometry, lengths are written as given, like `2.5cm`
+#[derive(Debug, Clone, Default)]
+pub struct Geometry {
+    /// Margin on every side `(margin=...)`
+    pub margin: Option<String>,
+    pub top: Option<String>,
+    pub bottom: Option<String>,
+    pub left: Option<String>,
+    pub right: Option<String>,
+    /// Paper, like `a4paper`
+    pub paper: Option<String>,
+    pub landscape: bool,
+}
+
+/// Headers and footers of a `fancyhdr` page style, content is already escaped
+#[derive(Debug, Clone)]
+pub struct PageStyle {
+    /// Name of the style, `fancy` or one to redefine like `plain`
+    pub name: String,
+    pub head: Vec<(Position, String)>,
+    pub foot: Vec<(Position, String)>,
+    /// Width of the rule under the header, like `0.4pt` `(\headrulewidth)`
+    pub head_rule: Option<String>,
+    /// Width of the rule over the footer `(\footrulewidth)`
+    pub foot_rule: Option<String>,
+}
+
+/// Position in a header or footer
+/// - Left `(L)`
+/// - Center `(C)`
+/// - Right `(R)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    Left,
+    Center,
+    Right,
+}
+
+/// Page numbering styles
+/// - Arabic `(1, 2, 3)`
+/// - Roman `(i, ii, iii)`
+/// - UpperRoman `(I, II, III)`
+/// - Alph `(a, b, c)`
+/// - UpperAlph `(A, B, C)`
+/// - Gobble, no page numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageNumbering {
+    Arabic,
+    Roman,
+    UpperRoman,
+    Alph,
+    UpperAlph,
+    Gobble,
+}
+
+/// Line spacing
+/// - Single `(\singlespacing)`
+/// - OneHalf `(\onehalfspacing)`
+/// - Double `(\doublespacing)`
+/// - Stretch by a factor `(\setstretch{...})`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineSpacing {
+    Single,
+    OneHalf,
+    Double,
+    Stretch(f32),
+}
+
+// Implementations //
+impl PageLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn set_geometry(&mut self, geometry: Geometry) {
+        self.geometry = Some(geometry);
+    }
+    /// Adds a page style, a style with the same name is replaced
+    pub fn add_page_style(&mut self, style: PageStyle) {
+        self.page_styles.retain(|s| s.name != style.name);
+        self.page_styles.push(style);
+    }
+    /// Sets the page style used for the document, like `fancy`
+    pub fn set_page_style(&mut self, name: &str) {
+        self.page_style = Some(name.to_string());
+    }
+    pub fn set_numbering(&mut self, numbering: PageNumbering) {
+        self.numbering = Some(numbering);
+    }
+    pub fn set_spacing(&mut self, spacing: LineSpacing) {
+        self.spacing = Some(spacing);
+    }
+    /// Packages needed by the layout, with the geometry as options of `geometry`
+    pub fn packages(&self) -> Vec<Package> {
+        let mut v = Vec::new();
+        if let Some(g) = &self.geometry {
+            let options = g.options();
+            let options: Vec<&str> = options.iter().map(|o| o.as_str()).collect();
+            v.push(Package::with_options("geometry", &options));
+        }
+        // Defined styles need it, and so does `fancy` even when it is not set up
+        if self.page_style.as_deref() == Some("fancy") || !self.page_styles.is_empty() {
+            v.push(Package::new("fancyhdr"));
+        }
+        if self.spacing.is_some() {
+            v.push(Package::new("setspace"));
+        }
+        v
+    }
+    /// Lines written in the preamble after the packages
+    pub fn preamble(&self) -> Vec<String> {
+        let mut v: Vec<String> = self.page_styles.iter().map(|s| s.to_latex_string()).collect();
+        if let Some(s) = &self.page_style {
+            v.push(format!("\\pagestyle{{{}}}", s));
+        }
+        if let Some(n) = &self.numbering {
+            v.push(n.to_latex_string());
+        }
+        if let Some(s) = &self.spacing {
+            v.push(s.to_latex_string());
+        }
+        v
+    }
+}
+
+impl Geometry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets the same margin on every side
+    pub fn set_margin(&mut self, margin: &str) {
+        self.margin = Some(margin.to_string());
+    }
+    /// Sets the margin of each side
+    pub fn set_margins(&mut self, top: &str, bottom: &str, left: &str, right: &str) {
+        self.top = Some(top.to_string());
+        self.bottom = Some(bottom.to_string());
+        self.left = Some(left.to_string());
+        self.right = Some(right.to_string());
+    }
+    /// Sets the paper, like `a4paper`
+    pub fn set_paper(&mut self, paper: &str) {
+        self.paper = Some(paper.to_string());
+    }
+    pub fn set_landscape(&mut self, landscape: bool) {
+        self.landscape = landscape;
+    }
+    /// Options of the `geometry` package
+    pub fn options(&self) -> Vec<String> {
+        let mut v = Vec::new();
+        if let Some(p) = &self.paper {
+            v.push(p.clone());
+        }
+        if self.landscape {
+            v.push("landscape".to_string());
+        }
+        let lengths = [
+            ("margin", &self.margin),
+            ("top", &self.top),
+            ("bottom", &self.bottom),
+            ("left", &self.left),
+            ("right", &self.right),
+        ];
+        for (name, length) in lengths {
+            if let Some(l) = length {
+                v.push(format!("{}={}", name, l));
+            }
+        }
+        v
+    }
+}
+
+impl PageStyle {
+    /// Creates an empty page style, `fancy` is the one of `fancyhdr`
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            head: Vec::new(),
+            foot: Vec::new(),
+            head_rule: None,
+            foot_rule: None,
+        }
+    }
+    /// Sets the header at a position, escaping it unless it is `Raw`,
+    /// like `Raw::new("\\thepage")`
+    pub fn set_head(&mut self, position: Position, content: impl Convert) {
+        self.head.retain(|(p, _)| *p != position);
+        self.head.push((position, content.to_latex_string()));
+    }
+    /// Sets the footer at a position, escaping it unless it is `Raw`
+    pub fn set_foot(&mut self, position: Position, content: impl Convert) {
+        self.foot.retain(|(p, _)| *p != position);
+        self.foot.push((position, content.to_latex_string()));
+    }
+    /// Sets the width of the rule under the header, `0pt` to remove it
+    pub fn set_head_rule(&mut self, width: &str) {
+        self.head_rule = Some(width.to_string());
+    }
+    /// Sets the width of the rule over the footer
+    pub fn set_foot_rule(&mut self, width: &str) {
+        self.foot_rule = Some(width.to_string());
+    }
+}
+
+// Trait Implementations //
+impl Convert for Position {
+    fn to_latex_string(&self) -> String {
+        match self {
+            Position::Left => "L".to_string(),
+            Position::Center => "C".to_string(),
+            Position::Right => "R".to_string(),
+        }
+    }
+}
+
+/// `fancy` is set up directly, other styles are redefined with `\fancypagestyle`
+impl Convert for PageStyle {
+    fn to_latex_string(&self) -> String {
+        let mut s = vec!["\\fancyhf{}".to_string()];
+        for (p, c) in &self.head {
+            s.push(format!("\\fancyhead[{}]{{{}}}", p.to_latex_string(), c));
+        }
+        for (p, c) in &self.foot {
+            s.push(format!("\\fancyfoot[{}]{{{}}}", p.to_latex_string(), c));
+        }
+        if let Some(w) = &self.head_rule {
+            s.push(format!("\\renewcommand{{\\headrulewidth}}{{{}}}", w));
+        }
+        if let Some(w) = &self.foot_rule {
+            s.push(format!("\\renewcommand{{\\footrulewidth}}{{{}}}", w));
+        }
+        match self.name.as_str() {
+            "fancy" => s.join("\n"),
+            name => format!("\\fancypagestyle{{{}}}{{\n{}\n}}", name, s.join("\n")),
+        }
+    }
+}
+
+impl Convert for PageNumbering {
+    fn to_latex_string(&self) -> String {
+        let style = match self {
+            PageNumbering::Arabic => "arabic",
+            PageNumbering::Roman => "roman",
+            PageNumbering::UpperRoman => "Roman",
+            PageNumbering::Alph => "alph",
+            PageNumbering::UpperAlph => "Alph",
+            PageNumbering::Gobble => "gobble",
+        };
+        format!("\\pagenumbering{{{}}}", style)
+    }
+}
+
+impl Convert for LineSpacing {
+    fn to_latex_string(&self) -> String {
+        match self {
+            LineSpacing::Single => "\\singlespacing".to_string(),
+            LineSpacing::OneHalf => "\\onehalfspacing".to_string(),
+            LineSpacing::Double => "\\doublespacing".to_string(),
+            LineSpacing::Stretch(f) => format!("\\setstretch{{{}}}", f),
+        }
+    }
+}
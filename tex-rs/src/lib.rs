@@ -6,7 +6,7 @@
 //! of attaching document elements. The example below shows how to utilize this library: 
 
 
-//! ```
+//! ```no_run
 //! use tex_rs::*;
 //! use std::path::Path;
 //! 
@@ -32,7 +32,7 @@
 //! 
 //!     part_two.attach(Element::from(env)).unwrap();
 //! 
-//!     let mut list = List::new(ListMode::Enumerate, &vec!["item 1".to_owned(), "item 2".to_owned(), "item 3".to_owned()]);
+//!     let mut list = List::new(ListMode::Enumerate, &["item 1".to_owned(), "item 2".to_owned(), "item 3".to_owned()]);
 //! 
 //!     part_two.attach(Element::from(list)).unwrap();
 //! 
@@ -43,17 +43,64 @@
 
 //! ```
 
+pub use bibliography::*;
+pub use compile::*;
+#[cfg(feature = "csv")]
+pub use data::*;
 pub use element::*;
 pub use error::*;
+pub use figure::*;
+pub use frame::*;
+pub use hyperref::*;
 pub use latex::*;
+pub use layout::*;
+pub use log::*;
+pub use math::*;
+pub use parse::*;
+pub use reference::*;
+pub use source_map::*;
+pub use span::*;
+pub use style::*;
+pub use table::*;
 pub use traits::*;
 
 
+/// Contains the bibliography, its entries and citations
+pub mod bibliography;
+/// Contains the compiler that runs a local TeX engine
+pub mod compile;
+/// Contains the constructors that build tables from CSV and serde records
+#[cfg(feature = "csv")]
+pub mod data;
 /// Contains all Element related structs/enums
 pub mod element;
 /// Contains all custom error handling 
 pub mod error;
+/// Contains the Figure element and its images
+pub mod figure;
+/// Contains the beamer Frame element, its blocks and columns
+pub mod frame;
+/// Contains links, urls and the hyperref setup with the PDF properties
+pub mod hyperref;
 /// Contains all of the core functionality with the Latex struct
 pub mod latex;
+/// Contains the page layout with geometry, headers, footers and line spacing
+pub mod layout;
+/// Contains the parser that turns LaTeX logs into diagnostics
+pub mod log;
+/// Contains math expressions and the equation elements that use them
+pub mod math;
+/// Contains the parser that reads LaTeX source into a Latex struct
+pub mod parse;
+/// Contains references to labels and the checks for dangling references
+pub mod reference;
+/// Contains the source map from written lines back to elements
+pub mod source_map;
+/// Contains inline spans and the rich text paragraphs made of them
+pub mod span;
+/// Contains text styles with fonts, sizes and colors
+pub mod style;
+/// Contains the Table element and its rows and cells
+pub mod table;
 /// Contains all of the traits for the structs/enums
 pub mod traits;
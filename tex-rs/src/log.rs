@@ -0,0 +1,219 @@
+use crate::error::TexError;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+/// Diagnostic found in a LaTeX log, with the file and line it points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// What kind of problem it is
+    pub kind: DiagnosticKind,
+    /// The message from the log, joined onto one line
+    pub message: String,
+    /// The file that was being read, if known
+    pub file: Option<String>,
+    /// The input line, if known
+    pub line: Option<usize>,
+}
+
+/// Kinds of diagnostics
+/// - Error `(! ...)`
+/// - OverfullBox `(Overfull \hbox ...)`
+/// - UnderfullBox `(Underfull \hbox ...)`
+/// - UndefinedReference `(Reference ... undefined)`
+/// - UndefinedCitation `(Citation ... undefined)`
+/// - MissingFile `(File ... not found)`
+/// - FontWarning `(LaTeX Font Warning: ...)`
+/// - Warning (any other warning)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    Error,
+    OverfullBox,
+    UnderfullBox,
+    UndefinedReference,
+    UndefinedCitation,
+    MissingFile,
+    FontWarning,
+    Warning,
+}
+
+// Implementations //
+impl Diagnostic {
+    pub fn new(kind: DiagnosticKind, message: &str) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+            file: None,
+            line: None,
+        }
+    }
+    /// Errors and missing files stop a document from compiling
+    pub fn is_error(&self) -> bool {
+        matches!(self.kind, DiagnosticKind::Error | DiagnosticKind::MissingFile)
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{}: {}", file, line, self.message),
+            (Some(file), None) => write!(f, "{}: {}", file, self.message),
+            (None, Some(line)) => write!(f, "line {}: {}", line, self.message),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Parses a LaTeX log into diagnostics, in the order they appear
+pub fn parse_log(log: &str) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = log.lines().collect();
+    let mut diagnostics = Vec::new();
+    let mut files = FileStack::default();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let file = files.current();
+        files.update(line);
+
+        if let Some((file, line_no, message)) = file_line_error(line) {
+            let mut d = Diagnostic::new(error_kind(message), message);
+            d.file = Some(file.to_string());
+            d.line = Some(line_no);
+            diagnostics.push(d);
+        } else if let Some(message) = line.strip_prefix("! ") {
+            let mut d = Diagnostic::new(error_kind(message), message);
+            d.file = file;
+            // The line number comes a few lines later as `l.<n> ...`
+            d.line = lines[i + 1..]
+                .iter()
+                .take(20)
+                .find_map(|l| l.strip_prefix("l.").and_then(leading_number));
+            diagnostics.push(d);
+        } else if line.starts_with("Overfull \\") || line.starts_with("Underfull \\") {
+            let kind = match line.starts_with("Overfull") {
+                true => DiagnosticKind::OverfullBox,
+                false => DiagnosticKind::UnderfullBox,
+            };
+            let mut d = Diagnostic::new(kind, line);
+            d.file = file;
+            d.line = after(line, "at lines ").or_else(|| after(line, "at line "));
+            diagnostics.push(d);
+        } else if line.contains("Warning: ") || line.starts_with("Missing character: ") {
+            // Warnings continue until the next blank line
+            let mut message = line.to_string();
+            while i + 1 < lines.len() && !lines[i + 1].trim().is_empty() {
+                i += 1;
+                files.update(lines[i]);
+                message.push(' ');
+                message.push_str(continuation(lines[i]));
+            }
+            let mut d = Diagnostic::new(warning_kind(&message), &message);
+            d.file = file;
+            d.line = after(&message, "input line ");
+            diagnostics.push(d);
+        }
+        i += 1;
+    }
+    diagnostics
+}
+
+/// Reads a `.log` file and parses it, see `parse_log`
+pub fn parse_log_file(path: PathBuf) -> Result<Vec<Diagnostic>, TexError> {
+    let log = std::fs::read_to_string(&path).map_err(|e| TexError::io(&path, e))?;
+    Ok(parse_log(&log))
+}
+
+fn error_kind(message: &str) -> DiagnosticKind {
+    if message.contains("File `") && message.contains("' not found") {
+        DiagnosticKind::MissingFile
+    } else {
+        DiagnosticKind::Error
+    }
+}
+
+fn warning_kind(message: &str) -> DiagnosticKind {
+    if message.starts_with("LaTeX Font Warning") || message.starts_with("Missing character") {
+        DiagnosticKind::FontWarning
+    } else if message.contains("Reference `") && message.contains("undefined") {
+        DiagnosticKind::UndefinedReference
+    } else if message.contains("Citation `") && message.contains("undefined") {
+        DiagnosticKind::UndefinedCitation
+    } else if message.contains("File `") && message.contains("not found") {
+        DiagnosticKind::MissingFile
+    } else {
+        DiagnosticKind::Warning
+    }
+}
+
+/// Splits a `-file-line-error` style line (`./main.tex:12: message`)
+fn file_line_error(line: &str) -> Option<(&str, usize, &str)> {
+    let mut parts = line.splitn(3, ':');
+    let file = parts.next()?;
+    let line_no = parts.next()?.parse().ok()?;
+    let message = parts.next()?.trim();
+    if !file.contains('.') || file.contains(' ') || message.is_empty() {
+        return None;
+    }
+    Some((file, line_no, message))
+}
+
+/// Strips the `(package)` prefix that packages put on continued warning lines
+fn continuation(line: &str) -> &str {
+    let line = line.trim();
+    match line.strip_prefix('(').and_then(|l| l.split_once(')')) {
+        Some((_, rest)) => rest.trim(),
+        None => line,
+    }
+}
+
+/// Reads the number directly after `pattern`
+fn after(s: &str, pattern: &str) -> Option<usize> {
+    s.find(pattern).and_then(|i| leading_number(&s[i + pattern.len()..]))
+}
+
+fn leading_number(s: &str) -> Option<usize> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s[..end].parse().ok()
+}
+
+/// Tracks which file TeX is reading from the `(file ... )` groups in the log
+#[derive(Default)]
+struct FileStack(Vec<Option<String>>);
+
+impl FileStack {
+    fn current(&self) -> Option<String> {
+        self.0.iter().rev().find_map(|f| f.clone())
+    }
+    fn update(&mut self, line: &str) {
+        for (i, c) in line.char_indices() {
+            match c {
+                '(' => {
+                    let rest = &line[i + 1..];
+                    let end = rest
+                        .find(|c: char| c.is_whitespace() || c == '(' || c == ')')
+                        .unwrap_or(rest.len());
+                    let name = &rest[..end];
+                    self.0.push(is_file_name(name).then(|| name.to_string()));
+                }
+                ')' => {
+                    self.0.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// File names in logs look like `./main.tex` or `/usr/share/.../article.cls`
+fn is_file_name(name: &str) -> bool {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) => {
+            !stem.is_empty()
+                && !ext.is_empty()
+                && ext.len() <= 4
+                && ext.chars().all(|c| c.is_ascii_alphabetic())
+                && !stem.starts_with(|c: char| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
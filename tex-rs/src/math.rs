@@ -0,0 +1,892 @@
+use crate::reference::arguments;
+use crate::Convert;
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Commands in math that need `amsmath`
+const AMSMATH_COMMANDS: [&str; 5] = ["text", "operatorname", "eqref", "dfrac", "tfrac"];
+
+/// Environments that need `amsmath`
+const AMSMATH_ENVIRONMENTS: [&str; 18] = [
+    "equation*",
+    "align",
+    "align*",
+    "alignat",
+    "alignat*",
+    "gather",
+    "gather*",
+    "multline",
+    "multline*",
+    "split",
+    "cases",
+    "matrix",
+    "pmatrix",
+    "bmatrix",
+    "Bmatrix",
+    "vmatrix",
+    "Vmatrix",
+    "smallmatrix",
+];
+
+/// Functions written as upright operators `(\sin, \log, ...)`
+const FUNCTIONS: [&str; 18] = [
+    "sin", "cos", "tan", "sec", "csc", "cot", "arcsin", "arccos", "arctan", "sinh", "cosh",
+    "tanh", "log", "ln", "exp", "det", "max", "min",
+];
+
+/// Math expression, built with the constructors on `Expr` and the
+/// `+`, `-`, `*`, `/` operators, written with as few parentheses as needed
+#[derive(Debug, Clone)]
+pub enum Expr {
+    /// Number as written
+    Num(String),
+    /// Variable, names longer than one letter are upright, escaped when written
+    Var(String),
+    /// Greek letter, capital when true
+    Greek(Greek, bool),
+    /// Binary operation
+    Binary(Box<Expr>, BinOp, Box<Expr>),
+    /// Relation between two sides, like an equation
+    Relation(Box<Expr>, Relation, Box<Expr>),
+    /// Negation `(-x)`
+    Neg(Box<Expr>),
+    /// Fraction `(\frac{...}{...})`
+    Frac(Box<Expr>, Box<Expr>),
+    /// Superscript `(x^{...})`
+    Pow(Box<Expr>, Box<Expr>),
+    /// Subscript `(x_{...})`
+    Index(Box<Expr>, Box<Expr>),
+    /// Root with an optional degree `(\sqrt[n]{...})`
+    Sqrt(Box<Expr>, Option<Box<Expr>>),
+    /// Sums, products, integrals and limits with their bounds and body
+    BigOp(BigOperator, Option<Box<Expr>>, Option<Box<Expr>>, Box<Expr>),
+    /// Function applied to arguments `(\sin\left(x\right))`, names that are
+    /// not operators like `\sin` are written like variables
+    Function(String, Vec<Expr>),
+    /// Parentheses `(\left( ... \right))`
+    Group(Box<Expr>),
+    /// Matrix with its delimiters, rows of cells
+    Matrix(Delimiter, Vec<Vec<Expr>>),
+    /// Text in math `(\text{...})`, already escaped
+    Text(String),
+    /// LaTeX written as is, its packages are only found with `Latex::set_scan_raw_packages`
+    Raw(String),
+}
+
+/// Binary operators
+/// - Add `(+)`
+/// - Sub `(-)`
+/// - Mul `(\cdot)`
+/// - Times `(\times)`
+/// - Div `(/)`
+/// - Implicit multiplication, written side by side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Times,
+    Div,
+    Implicit,
+}
+
+/// Relations
+/// - Eq `(=)`
+/// - Neq `(\neq)`
+/// - Lt `(<)`
+/// - Le `(\leq)`
+/// - Gt `(>)`
+/// - Ge `(\geq)`
+/// - Approx `(\approx)`
+/// - Equiv `(\equiv)`
+/// - Propto `(\propto)`
+/// - To `(\to)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Eq,
+    Neq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Approx,
+    Equiv,
+    Propto,
+    To,
+}
+
+/// Large operators
+/// - Sum `(\sum)`
+/// - Prod `(\prod)`
+/// - Integral with the variable of integration `(\int ... \,dx)`
+/// - Lim `(\lim)`, only the lower bound is written
+#[derive(Debug, Clone)]
+pub enum BigOperator {
+    Sum,
+    Prod,
+    Integral(String),
+    Lim,
+}
+
+/// Matrix delimiters
+/// - None `(matrix)`
+/// - Paren `(pmatrix)`
+/// - Bracket `(bmatrix)`
+/// - Brace `(Bmatrix)`
+/// - Bar, a determinant `(vmatrix)`
+/// - DoubleBar, a norm `(Vmatrix)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    None,
+    Paren,
+    Bracket,
+    Brace,
+    Bar,
+    DoubleBar,
+}
+
+/// How numeric grids are written as matrices
+#[derive(Debug, Clone)]
+pub struct MatrixFormat {
+    pub delimiter: Delimiter,
+    /// Decimals of every number, numbers are written as given when `None`
+    pub precision: Option<usize>,
+    /// Numbers are written as `1.23 \times 10^{4}` when true
+    pub scientific: bool,
+    /// Largest number of rows written counting the dots, at least 3, the rows
+    /// in between are elided with `\vdots`
+    pub max_rows: Option<usize>,
+    /// Largest number of columns written counting the dots, at least 3, the
+    /// columns in between are elided with `\cdots`
+    pub max_columns: Option<usize>,
+}
+
+/// Greek letters, capitals that look like Latin letters are written as them
+/// and so is the lowercase omicron
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Greek {
+    Alpha,
+    Beta,
+    Gamma,
+    Delta,
+    Epsilon,
+    Zeta,
+    Eta,
+    Theta,
+    Iota,
+    Kappa,
+    Lambda,
+    Mu,
+    Nu,
+    Xi,
+    Omicron,
+    Pi,
+    Rho,
+    Sigma,
+    Tau,
+    Upsilon,
+    Phi,
+    Chi,
+    Psi,
+    Omega,
+}
+
+/// Inline math `(\( ... \))`, used in text, cells and captions
+#[derive(Debug, Clone)]
+pub struct Math(pub Expr);
+
+/// Equation on its own line, numbered unless it is starred
+#[derive(Debug, Clone)]
+pub struct Equation {
+    pub expr: Expr,
+    /// `\label{...}`, only written when numbered
+    pub label: Option<String>,
+    /// `equation` when true, `equation*` otherwise
+    pub numbered: bool,
+}
+
+/// Equations aligned at their relations, one per row
+#[derive(Debug, Clone)]
+pub struct Align {
+    /// Rows with their labels, the first relation of a row is aligned
+    pub rows: Vec<(Expr, Option<String>)>,
+    /// `align` when true, `align*` otherwise
+    pub numbered: bool,
+}
+
+// Implementations //
+impl Expr {
+    pub fn num(n: impl Display) -> Self {
+        Expr::Num(n.to_string())
+    }
+    pub fn var(name: &str) -> Self {
+        Expr::Var(name.to_string())
+    }
+    pub fn greek(letter: Greek) -> Self {
+        Expr::Greek(letter, false)
+    }
+    pub fn capital(letter: Greek) -> Self {
+        Expr::Greek(letter, true)
+    }
+    pub fn frac(num: impl Into<Expr>, den: impl Into<Expr>) -> Self {
+        Expr::Frac(Box::new(num.into()), Box::new(den.into()))
+    }
+    pub fn sqrt(expr: impl Into<Expr>) -> Self {
+        Expr::Sqrt(Box::new(expr.into()), None)
+    }
+    pub fn root(degree: impl Into<Expr>, expr: impl Into<Expr>) -> Self {
+        Expr::Sqrt(Box::new(expr.into()), Some(Box::new(degree.into())))
+    }
+    pub fn sum(lower: impl Into<Expr>, upper: impl Into<Expr>, body: impl Into<Expr>) -> Self {
+        Self::big(BigOperator::Sum, Some(lower.into()), Some(upper.into()), body)
+    }
+    pub fn prod(lower: impl Into<Expr>, upper: impl Into<Expr>, body: impl Into<Expr>) -> Self {
+        Self::big(BigOperator::Prod, Some(lower.into()), Some(upper.into()), body)
+    }
+    /// Definite integral of `body` over `var`
+    pub fn integral(
+        lower: impl Into<Expr>,
+        upper: impl Into<Expr>,
+        body: impl Into<Expr>,
+        var: &str,
+    ) -> Self {
+        let op = BigOperator::Integral(var.to_string());
+        Self::big(op, Some(lower.into()), Some(upper.into()), body)
+    }
+    /// Limit of `body` as `var` goes to `to`
+    pub fn lim(var: impl Into<Expr>, to: impl Into<Expr>, body: impl Into<Expr>) -> Self {
+        let under = var.into().rel(Relation::To, to);
+        Self::big(BigOperator::Lim, Some(under), None, body)
+    }
+    /// Large operator with optional bounds
+    pub fn big(
+        op: BigOperator,
+        lower: Option<Expr>,
+        upper: Option<Expr>,
+        body: impl Into<Expr>,
+    ) -> Self {
+        Expr::BigOp(
+            op,
+            lower.map(Box::new),
+            upper.map(Box::new),
+            Box::new(body.into()),
+        )
+    }
+    /// Function applied to one argument
+    pub fn func(name: &str, arg: impl Into<Expr>) -> Self {
+        Expr::Function(name.to_string(), vec![arg.into()])
+    }
+    pub fn group(expr: impl Into<Expr>) -> Self {
+        Expr::Group(Box::new(expr.into()))
+    }
+    pub fn matrix(delimiter: Delimiter, rows: Vec<Vec<Expr>>) -> Self {
+        Expr::Matrix(delimiter, rows)
+    }
+    /// Matrix of a numeric grid, like `Vec<Vec<f64>>`, written with the format
+    pub fn from_grid<T: Display>(grid: &[Vec<T>], format: &MatrixFormat) -> Self {
+        let rows = elide(grid.len(), format.max_rows);
+        let width = grid.iter().map(|r| r.len()).max().unwrap_or(0);
+        let columns = elide(width, format.max_columns);
+        let cells = rows
+            .iter()
+            .map(|r| {
+                columns
+                    .iter()
+                    .map(|c| match (r, c) {
+                        (None, None) => Expr::Raw("\\ddots".to_string()),
+                        (None, Some(_)) => Expr::Raw("\\vdots".to_string()),
+                        (Some(_), None) => Expr::Raw("\\cdots".to_string()),
+                        (Some(r), Some(c)) => match grid[*r].get(*c) {
+                            Some(cell) => format.number(&cell.to_string()),
+                            None => Expr::Raw(String::new()),
+                        },
+                    })
+                    .collect()
+            })
+            .collect();
+        Expr::Matrix(format.delimiter, cells)
+    }
+    /// Column vector of numbers, written with the format
+    pub fn from_vector<T: Display>(values: &[T], format: &MatrixFormat) -> Self {
+        let grid: Vec<Vec<&T>> = values.iter().map(|v| vec![v]).collect();
+        Self::from_grid(&grid, format)
+    }
+    /// Text in math, escaping it unless it is `Raw`
+    pub fn text(text: impl Convert) -> Self {
+        Expr::Text(text.to_latex_string())
+    }
+    /// Raises to a power
+    pub fn pow(self, exp: impl Into<Expr>) -> Self {
+        Expr::Pow(Box::new(self), Box::new(exp.into()))
+    }
+    /// Adds a subscript
+    pub fn index(self, index: impl Into<Expr>) -> Self {
+        Expr::Index(Box::new(self), Box::new(index.into()))
+    }
+    /// Joins with another expression by an operator
+    pub fn op(self, op: BinOp, rhs: impl Into<Expr>) -> Self {
+        Expr::Binary(Box::new(self), op, Box::new(rhs.into()))
+    }
+    /// Relates to another expression
+    pub fn rel(self, relation: Relation, rhs: impl Into<Expr>) -> Self {
+        Expr::Relation(Box::new(self), relation, Box::new(rhs.into()))
+    }
+    /// Shorthand for `rel(Relation::Eq, rhs)`
+    pub fn equals(self, rhs: impl Into<Expr>) -> Self {
+        self.rel(Relation::Eq, rhs)
+    }
+    /// How tightly the expression binds, parts that bind looser than
+    /// their surroundings are put in parentheses
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Relation(..) => 0,
+            Expr::Binary(_, BinOp::Add | BinOp::Sub, _) | Expr::BigOp(..) => 1,
+            Expr::Binary(..) => 2,
+            Expr::Neg(_) => 3,
+            Expr::Num(n) if n.starts_with('-') => 3,
+            Expr::Pow(..) | Expr::Index(..) | Expr::Frac(..) => 4,
+            _ => 5,
+        }
+    }
+    /// Checks if the expression is written with a leading minus
+    fn is_negative(&self) -> bool {
+        match self {
+            Expr::Neg(_) => true,
+            Expr::Num(n) => n.starts_with('-'),
+            _ => false,
+        }
+    }
+    /// Writes the expression, with parentheses when it binds looser than `min`
+    fn wrapped(&self, min: u8) -> String {
+        match self.precedence() < min {
+            true => format!("\\left({}\\right)", self.to_latex_string()),
+            false => self.to_latex_string(),
+        }
+    }
+    /// Writes a row of an `align`, with `&` before the first relation
+    fn aligned(&self) -> String {
+        match self {
+            Expr::Relation(lhs, relation, rhs) => format!(
+                "{} &{} {}",
+                lhs.to_latex_string(),
+                relation.to_latex_string(),
+                rhs.to_latex_string()
+            ),
+            e => format!("& {}", e.to_latex_string()),
+        }
+    }
+    fn needs_amsmath(&self) -> bool {
+        match self {
+            Expr::Text(_) | Expr::Matrix(..) => true,
+            Expr::Binary(lhs, _, rhs)
+            | Expr::Relation(lhs, _, rhs)
+            | Expr::Frac(lhs, rhs)
+            | Expr::Pow(lhs, rhs)
+            | Expr::Index(lhs, rhs) => lhs.needs_amsmath() || rhs.needs_amsmath(),
+            Expr::Neg(e) | Expr::Group(e) => e.needs_amsmath(),
+            Expr::Sqrt(e, n) => e.needs_amsmath() || n.as_ref().is_some_and(|n| n.needs_amsmath()),
+            Expr::BigOp(_, lower, upper, body) => {
+                [lower, upper].iter().any(|b| b.as_ref().is_some_and(|b| b.needs_amsmath()))
+                    || body.needs_amsmath()
+            }
+            Expr::Function(_, args) => args.iter().any(|a| a.needs_amsmath()),
+            Expr::Num(_) | Expr::Var(_) | Expr::Greek(..) | Expr::Raw(_) => false,
+        }
+    }
+}
+
+impl MatrixFormat {
+    pub fn new(delimiter: Delimiter) -> Self {
+        Self {
+            delimiter,
+            precision: None,
+            scientific: false,
+            max_rows: None,
+            max_columns: None,
+        }
+    }
+    /// Sets the number of decimals
+    pub fn set_precision(&mut self, precision: usize) {
+        self.precision = Some(precision);
+    }
+    /// Sets if numbers are written in scientific notation
+    pub fn set_scientific(&mut self, scientific: bool) {
+        self.scientific = scientific;
+    }
+    /// Sets the largest size written counting the dots, the first rows and columns
+    /// are kept with the last row and column, at least 3 of each are written
+    pub fn set_max_size(&mut self, rows: usize, columns: usize) {
+        self.max_rows = Some(rows);
+        self.max_columns = Some(columns);
+    }
+    /// Writes a number, cells that are not numbers are written as is
+    fn number(&self, cell: &str) -> Expr {
+        let n: f64 = match cell.trim().parse() {
+            Ok(n) => n,
+            Err(_) => return Expr::Raw(cell.to_string()),
+        };
+        if n.is_nan() {
+            return Expr::text("NaN");
+        }
+        if n.is_infinite() {
+            let infinity = Expr::Raw("\\infty".to_string());
+            return match n < 0.0 {
+                true => -infinity,
+                false => infinity,
+            };
+        }
+        let s = match (self.scientific, self.precision) {
+            (true, Some(p)) => format!("{:.*e}", p, n),
+            (true, None) => format!("{:e}", n),
+            (false, Some(p)) => format!("{:.*}", p, n),
+            (false, None) => cell.trim().to_string(),
+        };
+        match s.split_once('e') {
+            Some((mantissa, "0")) => Expr::Num(mantissa.to_string()),
+            Some((mantissa, exponent)) => Expr::Num(mantissa.to_string())
+                .op(BinOp::Times, Expr::num(10).pow(Expr::num(exponent))),
+            None => Expr::Num(s),
+        }
+    }
+}
+
+impl Default for MatrixFormat {
+    fn default() -> Self {
+        Self::new(Delimiter::Paren)
+    }
+}
+
+
+impl Equation {
+    pub fn new(expr: Expr) -> Self {
+        Self {
+            expr,
+            label: None,
+            numbered: true,
+        }
+    }
+    /// Sets the label used to reference the equation
+    pub fn set_label(&mut self, label: &str) {
+        self.label = Some(label.to_string());
+    }
+    /// Sets if the equation is numbered
+    pub fn set_numbered(&mut self, numbered: bool) {
+        self.numbered = numbered;
+    }
+    /// The label when the equation is unnumbered, since it is not written
+    pub fn unnumbered_labels(&self) -> Vec<String> {
+        match (self.numbered, &self.label) {
+            (false, Some(l)) => vec![l.clone()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Align {
+    pub fn new(rows: Vec<Expr>) -> Self {
+        Self {
+            rows: rows.into_iter().map(|r| (r, None)).collect(),
+            numbered: true,
+        }
+    }
+    /// Adds a row
+    pub fn add_row(&mut self, row: Expr) {
+        self.rows.push((row, None));
+    }
+    /// Adds a row with a label
+    pub fn add_labeled_row(&mut self, row: Expr, label: &str) {
+        self.rows.push((row, Some(label.to_string())));
+    }
+    /// Sets if the rows are numbered
+    pub fn set_numbered(&mut self, numbered: bool) {
+        self.numbered = numbered;
+    }
+    /// Labels of the rows when they are unnumbered, since they are not written
+    pub fn unnumbered_labels(&self) -> Vec<String> {
+        match self.numbered {
+            true => Vec::new(),
+            false => self.rows.iter().filter_map(|(_, l)| l.clone()).collect(),
+        }
+    }
+}
+
+impl Greek {
+    fn name(&self) -> &'static str {
+        match self {
+            Greek::Alpha => "alpha",
+            Greek::Beta => "beta",
+            Greek::Gamma => "gamma",
+            Greek::Delta => "delta",
+            Greek::Epsilon => "epsilon",
+            Greek::Zeta => "zeta",
+            Greek::Eta => "eta",
+            Greek::Theta => "theta",
+            Greek::Iota => "iota",
+            Greek::Kappa => "kappa",
+            Greek::Lambda => "lambda",
+            Greek::Mu => "mu",
+            Greek::Nu => "nu",
+            Greek::Xi => "xi",
+            Greek::Omicron => "omicron",
+            Greek::Pi => "pi",
+            Greek::Rho => "rho",
+            Greek::Sigma => "sigma",
+            Greek::Tau => "tau",
+            Greek::Upsilon => "upsilon",
+            Greek::Phi => "phi",
+            Greek::Chi => "chi",
+            Greek::Psi => "psi",
+            Greek::Omega => "omega",
+        }
+    }
+    /// Latin letter written for capitals that have no command
+    fn latin(&self) -> Option<&'static str> {
+        match self {
+            Greek::Alpha => Some("A"),
+            Greek::Beta => Some("B"),
+            Greek::Epsilon => Some("E"),
+            Greek::Zeta => Some("Z"),
+            Greek::Eta => Some("H"),
+            Greek::Iota => Some("I"),
+            Greek::Kappa => Some("K"),
+            Greek::Mu => Some("M"),
+            Greek::Nu => Some("N"),
+            Greek::Omicron => Some("O"),
+            Greek::Rho => Some("P"),
+            Greek::Tau => Some("T"),
+            Greek::Chi => Some("X"),
+            _ => None,
+        }
+    }
+}
+
+impl Delimiter {
+    fn environment(&self) -> &'static str {
+        match self {
+            Delimiter::None => "matrix",
+            Delimiter::Paren => "pmatrix",
+            Delimiter::Bracket => "bmatrix",
+            Delimiter::Brace => "Bmatrix",
+            Delimiter::Bar => "vmatrix",
+            Delimiter::DoubleBar => "Vmatrix",
+        }
+    }
+}
+
+/// Indices kept when `len` is cut down to `max`, `None` marks the elided ones,
+/// at least 3 are kept so the first, the dots and the last are written
+fn elide(len: usize, max: Option<usize>) -> Vec<Option<usize>> {
+    match max.map(|m| m.max(3)) {
+        Some(max) if len > max => {
+            let mut v: Vec<Option<usize>> = (0..max - 2).map(Some).collect();
+            v.push(None);
+            v.push(Some(len - 1));
+            v
+        }
+        _ => (0..len).map(Some).collect(),
+    }
+}
+
+/// Writes a variable or function name, escaping the characters that have a
+/// meaning in math, names longer than one letter are upright
+fn identifier(name: &str) -> String {
+    let mut s = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                s.push('\\');
+                s.push(c);
+            }
+            '~' => s.push_str("\\sim{}"),
+            '^' => s.push_str("\\wedge{}"),
+            '\\' => s.push_str("\\backslash{}"),
+            _ => s.push(c),
+        }
+    }
+    match name.chars().count() > 1 {
+        true => format!("\\mathrm{{{}}}", s),
+        false => s,
+    }
+}
+
+/// Packages needed by the math in LaTeX source written as is, `amsmath` for
+/// its commands and environments and `mathtools` for starred matrices like `pmatrix*`
+pub fn math_packages(source: &str) -> Vec<String> {
+    let environments = arguments(source, "begin");
+    let mut v = Vec::new();
+    let commands = AMSMATH_COMMANDS.iter().any(|c| !arguments(source, c).is_empty());
+    if commands || environments.iter().any(|e| AMSMATH_ENVIRONMENTS.contains(&e.as_str())) {
+        v.push("amsmath".to_string());
+    }
+    if environments.iter().any(|e| e.ends_with("matrix*")) {
+        v.push("mathtools".to_string());
+    }
+    v
+}
+
+// Trait Implementations //
+impl Convert for Expr {
+    fn to_latex_string(&self) -> String {
+        match self {
+            Expr::Num(n) => n.clone(),
+            Expr::Var(v) => identifier(v),
+            Expr::Greek(g, true) => match g.latin() {
+                Some(l) => l.to_string(),
+                None => {
+                    let name = g.name();
+                    format!("\\{}{}", name[..1].to_uppercase(), &name[1..])
+                }
+            },
+            // There is no `\omicron`, it looks like a Latin `o`
+            Expr::Greek(Greek::Omicron, false) => "o".to_string(),
+            Expr::Greek(g, false) => format!("\\{}", g.name()),
+            Expr::Binary(lhs, op, rhs) => {
+                let min = self.precedence();
+                // `a - (b - c)` and `a / (b / c)` keep their parentheses,
+                // and so does a negative right operand like `2 \cdot (-3)`
+                let right = match op {
+                    _ if rhs.is_negative() => rhs.wrapped(u8::MAX),
+                    BinOp::Sub | BinOp::Div => rhs.wrapped(min + 1),
+                    _ => rhs.wrapped(min),
+                };
+                match op {
+                    BinOp::Implicit => format!("{} {}", lhs.wrapped(min), right),
+                    op => format!("{} {} {}", lhs.wrapped(min), op.to_latex_string(), right),
+                }
+            }
+            Expr::Relation(lhs, relation, rhs) => format!(
+                "{} {} {}",
+                lhs.to_latex_string(),
+                relation.to_latex_string(),
+                rhs.to_latex_string()
+            ),
+            Expr::Neg(e) if e.is_negative() => format!("-{}", e.wrapped(u8::MAX)),
+            Expr::Neg(e) => format!("-{}", e.wrapped(3)),
+            Expr::Frac(num, den) => format!(
+                "\\frac{{{}}}{{{}}}",
+                num.to_latex_string(),
+                den.to_latex_string()
+            ),
+            Expr::Pow(base, exp) => format!("{}^{{{}}}", base.wrapped(5), exp.to_latex_string()),
+            Expr::Index(base, index) => {
+                format!("{}_{{{}}}", base.wrapped(5), index.to_latex_string())
+            }
+            Expr::Sqrt(e, None) => format!("\\sqrt{{{}}}", e.to_latex_string()),
+            Expr::Sqrt(e, Some(n)) => format!(
+                "\\sqrt[{}]{{{}}}",
+                n.to_latex_string(),
+                e.to_latex_string()
+            ),
+            Expr::BigOp(op, lower, upper, body) => {
+                let mut s = op.to_latex_string();
+                if let Some(l) = lower {
+                    s.push_str(&format!("_{{{}}}", l.to_latex_string()));
+                }
+                if let Some(u) = upper {
+                    s.push_str(&format!("^{{{}}}", u.to_latex_string()));
+                }
+                s.push(' ');
+                s.push_str(&body.wrapped(2));
+                if let BigOperator::Integral(var) = op {
+                    s.push_str(&format!(" \\,d{}", identifier(var)));
+                }
+                s
+            }
+            Expr::Function(name, args) => {
+                let args: Vec<String> = args.iter().map(|a| a.to_latex_string()).collect();
+                let name = match FUNCTIONS.contains(&name.as_str()) {
+                    true => format!("\\{}", name),
+                    false => identifier(name),
+                };
+                format!("{}\\left({}\\right)", name, args.join(", "))
+            }
+            Expr::Group(e) => format!("\\left({}\\right)", e.to_latex_string()),
+            Expr::Matrix(delimiter, rows) => {
+                let rows: Vec<String> = rows
+                    .iter()
+                    .map(|r| {
+                        let cells: Vec<String> = r.iter().map(|c| c.to_latex_string()).collect();
+                        cells.join(" & ")
+                    })
+                    .collect();
+                let env = delimiter.environment();
+                format!("\\begin{{{}}}\n{}\n\\end{{{}}}", env, rows.join(" \\\\\n"), env)
+            }
+            Expr::Text(t) => format!("\\text{{{}}}", t),
+            Expr::Raw(r) => r.clone(),
+        }
+    }
+    /// Packages needed by the expression, `amsmath` for text and matrices
+    fn packages(&self) -> Vec<String> {
+        match self.needs_amsmath() {
+            true => vec!["amsmath".to_string()],
+            false => Vec::new(),
+        }
+    }
+}
+
+impl Convert for BinOp {
+    fn to_latex_string(&self) -> String {
+        match self {
+            BinOp::Add => "+".to_string(),
+            BinOp::Sub => "-".to_string(),
+            BinOp::Mul => "\\cdot".to_string(),
+            BinOp::Times => "\\times".to_string(),
+            BinOp::Div => "/".to_string(),
+            BinOp::Implicit => String::new(),
+        }
+    }
+}
+
+impl Convert for Relation {
+    fn to_latex_string(&self) -> String {
+        match self {
+            Relation::Eq => "=".to_string(),
+            Relation::Neq => "\\neq".to_string(),
+            Relation::Lt => "<".to_string(),
+            Relation::Le => "\\leq".to_string(),
+            Relation::Gt => ">".to_string(),
+            Relation::Ge => "\\geq".to_string(),
+            Relation::Approx => "\\approx".to_string(),
+            Relation::Equiv => "\\equiv".to_string(),
+            Relation::Propto => "\\propto".to_string(),
+            Relation::To => "\\to".to_string(),
+        }
+    }
+}
+
+impl Convert for BigOperator {
+    fn to_latex_string(&self) -> String {
+        match self {
+            BigOperator::Sum => "\\sum".to_string(),
+            BigOperator::Prod => "\\prod".to_string(),
+            BigOperator::Integral(_) => "\\int".to_string(),
+            BigOperator::Lim => "\\lim".to_string(),
+        }
+    }
+}
+
+impl Convert for Math {
+    fn to_latex_string(&self) -> String {
+        format!("\\({}\\)", self.0.to_latex_string())
+    }
+    /// Packages needed by the expression
+    fn packages(&self) -> Vec<String> {
+        self.0.packages()
+    }
+}
+
+impl Convert for Equation {
+    fn to_latex_string(&self) -> String {
+        let expr = self.expr.to_latex_string();
+        match (self.numbered, &self.label) {
+            (true, Some(l)) => format!(
+                "\\begin{{equation}}\\label{{{}}}\n{}\n\\end{{equation}}",
+                l, expr
+            ),
+            (true, None) => format!("\\begin{{equation}}\n{}\n\\end{{equation}}", expr),
+            (false, _) => format!("\\begin{{equation*}}\n{}\n\\end{{equation*}}", expr),
+        }
+    }
+    /// Packages needed by the equation, `amsmath` for `equation*`
+    fn packages(&self) -> Vec<String> {
+        match self.numbered {
+            true => self.expr.packages(),
+            false => vec!["amsmath".to_string()],
+        }
+    }
+}
+
+impl Convert for Align {
+    fn to_latex_string(&self) -> String {
+        let env = match self.numbered {
+            true => "align",
+            false => "align*",
+        };
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|(row, label)| match (label, self.numbered) {
+                (Some(l), true) => format!("{} \\label{{{}}}", row.aligned(), l),
+                _ => row.aligned(),
+            })
+            .collect();
+        format!("\\begin{{{}}}\n{}\n\\end{{{}}}", env, rows.join(" \\\\\n"), env)
+    }
+    /// Packages needed by the rows, `align` is from `amsmath`
+    fn packages(&self) -> Vec<String> {
+        vec!["amsmath".to_string()]
+    }
+}
+
+impl From<i64> for Expr {
+    fn from(n: i64) -> Self {
+        Expr::num(n)
+    }
+}
+
+impl From<i32> for Expr {
+    fn from(n: i32) -> Self {
+        Expr::num(n)
+    }
+}
+
+impl From<f64> for Expr {
+    fn from(n: f64) -> Self {
+        Expr::num(n)
+    }
+}
+
+impl From<&str> for Expr {
+    fn from(name: &str) -> Self {
+        Expr::var(name)
+    }
+}
+
+impl From<Vec<Vec<f64>>> for Expr {
+    fn from(grid: Vec<Vec<f64>>) -> Self {
+        Expr::from_grid(&grid, &MatrixFormat::default())
+    }
+}
+
+impl From<Greek> for Expr {
+    fn from(letter: Greek) -> Self {
+        Expr::greek(letter)
+    }
+}
+
+impl<T: Into<Expr>> Add<T> for Expr {
+    type Output = Expr;
+    fn add(self, rhs: T) -> Expr {
+        self.op(BinOp::Add, rhs)
+    }
+}
+
+impl<T: Into<Expr>> Sub<T> for Expr {
+    type Output = Expr;
+    fn sub(self, rhs: T) -> Expr {
+        self.op(BinOp::Sub, rhs)
+    }
+}
+
+impl<T: Into<Expr>> Mul<T> for Expr {
+    type Output = Expr;
+    fn mul(self, rhs: T) -> Expr {
+        self.op(BinOp::Mul, rhs)
+    }
+}
+
+/// Dividing gives a fraction, use `op(BinOp::Div, ...)` for a slash
+impl<T: Into<Expr>> Div<T> for Expr {
+    type Output = Expr;
+    fn div(self, rhs: T) -> Expr {
+        Expr::frac(self, rhs)
+    }
+}
+
+impl Neg for Expr {
+    type Output = Expr;
+    fn neg(self) -> Expr {
+        Expr::Neg(Box::new(self))
+    }
+}
@@ -0,0 +1,577 @@
+use crate::element::*;
+use crate::error::TexError;
+use crate::latex::{Class, ClassOption, FrontMatter, Latex, Metadata, Package};
+use crate::style::Style;
+use std::path::PathBuf;
+
+/// Sectioning commands that end a text paragraph
+const HEADINGS: [&str; 7] = [
+    "part",
+    "chapter",
+    "section",
+    "subsection",
+    "subsubsection",
+    "paragraph",
+    "subparagraph",
+];
+
+/// Parses LaTeX source into a Latex struct
+///
+/// - `\documentclass` goes into `document_class` (unknown classes become `Custom`)
+/// - `\usepackage[...]{...}` goes into packages, one per name with its options
+/// - `\author`, `\title` and `\date` go into metadata
+/// - `\part` down to `\subparagraph` are nested by level, with their starred
+///   and short title variants
+/// - `itemize`/`enumerate`/`description` become lists, other environments
+///   become environments with their options and arguments
+/// - `\input{...}` becomes an input and plain paragraphs become text
+/// - The title, abstract and lists at the start of the document go into
+///   `front_matter`, and `\appendix` and the book matter commands become `Matter`
+///
+/// Everything else becomes a `UserDefined`, at `Level::Package` in the preamble
+/// and at `Level::Body` in the document, so nothing is lost.
+/// Source without `\begin{document}` is read as a document body.
+pub fn parse(source: &str) -> Latex {
+    let mut latex = Latex::new();
+    latex.document_class = (Class::Article, None, None, Vec::new());
+    latex.metadata = Metadata::none();
+    latex.front_matter = FrontMatter::none();
+
+    let (preamble, body) = match source.find("\\begin{document}") {
+        Some(i) => {
+            let body = &source[i + "\\begin{document}".len()..];
+            let body = match body.find("\\end{document}") {
+                Some(j) => &body[..j],
+                None => body,
+            };
+            (&source[..i], body)
+        }
+        None => ("", source),
+    };
+
+    let mut elements = parse_preamble(preamble, &mut latex);
+    let start = parse_front(body, &mut latex.front_matter);
+    elements.extend(parse_body(&body[start..]));
+    latex.document_elements = elements;
+    latex
+}
+
+/// Reads a `.tex` file and parses it, see `parse`
+pub fn parse_file(path: PathBuf) -> Result<Latex, TexError> {
+    let source = std::fs::read_to_string(&path).map_err(|e| TexError::io(&path, e))?;
+    Ok(parse(&source))
+}
+
+fn parse_preamble(preamble: &str, latex: &mut Latex) -> Vec<Element> {
+    let mut ud = Vec::new();
+    let mut pos = skip_whitespace(preamble, 0);
+    while pos < preamble.len() {
+        let end = statement_end(preamble, pos);
+        let stmt = preamble[pos..end].trim_end();
+        let (name, args) = command(stmt);
+        match (name, args.as_slice()) {
+            ("documentclass", [opts @ .., class]) if opts.len() <= 1 => {
+                latex.document_class.0 = Class::from_class_str(class);
+                let opts = opts.iter().flat_map(|o| o.split(',')).map(str::trim);
+                for opt in opts.filter(|o| !o.is_empty()) {
+                    if let Some(size) = opt.strip_suffix("pt").and_then(|s| s.parse().ok()) {
+                        latex.document_class.1 = Some(size);
+                    } else if opt.ends_with("paper") {
+                        latex.document_class.2 = Some(opt.to_string());
+                    } else {
+                        latex.add_class_option(ClassOption::from_option_str(opt));
+                    }
+                }
+            }
+            ("usepackage", [opts @ .., pkgs]) if opts.len() <= 1 && stmt.ends_with('}') => {
+                let opts: Vec<&str> = opts
+                    .iter()
+                    .flat_map(|o| o.split(','))
+                    .map(str::trim)
+                    .filter(|o| !o.is_empty())
+                    .collect();
+                for pkg in pkgs.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                    latex.use_package(Package::with_options(pkg, &opts));
+                }
+            }
+            ("author", [a]) if is_group(stmt, name) => latex.metadata.author = Some(a.to_string()),
+            ("title", [t]) if is_group(stmt, name) => latex.metadata.title = Some(t.to_string()),
+            ("date", [d]) if is_group(stmt, name) => latex.metadata.date = Some(d.to_string()),
+            _ => ud.push(Element::from(UserDefined::new(stmt, Level::Package))),
+        }
+        pos = skip_whitespace(preamble, end);
+    }
+    ud
+}
+
+fn parse_body(body: &str) -> Vec<Element> {
+    let mut tree = Tree::default();
+    let mut pos = skip_whitespace(body, 0);
+    while pos < body.len() {
+        let rest = &body[pos..];
+        let (element, end) = if rest.starts_with("\\begin{") {
+            environment(body, pos)
+        } else if is_par(rest) {
+            let end = paragraph_end(body, pos);
+            let text = body[pos + "\\par".len()..end].trim();
+            (Some(Element::from(Text(text.to_string(), TextType::Normal, Style::default(), Vec::new()))), end)
+        } else if rest.starts_with('%') || rest.starts_with('\\') {
+            let end = statement_end(body, pos);
+            let stmt = body[pos..end].trim_end();
+            let (heading, label) = split_label(stmt);
+            let (name, args) = command(heading);
+            let element = match (parse_heading(heading), name, args.as_slice()) {
+                // The label of an unnumbered heading isn't written back
+                (Some((_, _, Numbering::Unnumbered)), ..) if label.is_some() => {
+                    Element::from(UserDefined::new(stmt, Level::Body))
+                }
+                (Some((name, title, numbering)), ..) => {
+                    heading_element(name, title, label, numbering)
+                }
+                _ if label.is_some() => Element::from(UserDefined::new(stmt, Level::Body)),
+                (_, "appendix", []) => Element::from(Matter::Appendix),
+                (_, "frontmatter", []) => Element::from(Matter::Front),
+                (_, "mainmatter", []) => Element::from(Matter::Main),
+                (_, "backmatter", []) => Element::from(Matter::Back),
+                (_, "input", [file]) if is_group(stmt, name) => {
+                    Element::from(Input(file.to_string()))
+                }
+                _ => Element::from(UserDefined::new(stmt, Level::Body)),
+            };
+            (Some(element), end)
+        } else {
+            let end = paragraph_end(body, pos);
+            let text = body[pos..end].trim_end();
+            (Some(Element::from(Text(text.to_string(), TextType::Normal, Style::default(), Vec::new()))), end)
+        };
+        if let Some(e) = element {
+            tree.push(e);
+        }
+        pos = skip_whitespace(body, end);
+    }
+    tree.finish()
+}
+
+/// Builds the element tree, keeping a stack of the open headings
+#[derive(Default)]
+struct Tree {
+    elements: Vec<Element>,
+    open: Vec<Element>,
+}
+
+impl Tree {
+    fn push(&mut self, element: Element) {
+        if let Element::Matter(_) = element {
+            // Matter is only used between top level elements
+            while !self.open.is_empty() {
+                self.close();
+            }
+            self.elements.push(element);
+        } else if is_heading(&element) {
+            let level = element.kind().level();
+            while self.open.last().is_some_and(|h| h.kind().level() >= level) {
+                self.close();
+            }
+            self.open.push(element);
+        } else {
+            self.add(element);
+        }
+    }
+    fn add(&mut self, element: Element) {
+        match self.open.last_mut() {
+            Some(Element::Part(p)) => p.1.push(element),
+            Some(Element::Chapter(c)) => c.1.push(element),
+            Some(Element::Section(s)) => s.1.push(element),
+            Some(Element::Subsection(s)) => s.1.push(element),
+            Some(Element::Subsubsection(s)) => s.1.push(element),
+            Some(Element::Paragraph(p)) => p.1.push(element),
+            Some(Element::Subparagraph(p)) => p.1.push(element),
+            _ => self.elements.push(element),
+        }
+    }
+    fn close(&mut self) {
+        if let Some(heading) = self.open.pop() {
+            self.add(heading);
+        }
+    }
+    fn finish(mut self) -> Vec<Element> {
+        while !self.open.is_empty() {
+            self.close();
+        }
+        self.elements
+    }
+}
+
+fn is_heading(element: &Element) -> bool {
+    matches!(
+        element,
+        Element::Part(_)
+            | Element::Chapter(_)
+            | Element::Section(_)
+            | Element::Subsection(_)
+            | Element::Subsubsection(_)
+            | Element::Paragraph(_)
+            | Element::Subparagraph(_)
+    )
+}
+
+/// Reads `\section{...}`, `\section*{...}` or `\section[...]{...}` and the other
+/// headings into the name, title and numbering
+fn parse_heading(stmt: &str) -> Option<(&str, &str, Numbering)> {
+    let rest = stmt.strip_prefix('\\')?;
+    let name = HEADINGS
+        .iter()
+        .copied()
+        .filter(|h| rest.starts_with(h))
+        .max_by_key(|h| h.len())?;
+    let mut pos = 1 + name.len();
+    let numbering = match stmt[pos..].chars().next()? {
+        '*' => {
+            pos += 1;
+            Numbering::Unnumbered
+        }
+        '[' => {
+            let (short, after) = bracket(stmt, pos)?;
+            pos = after;
+            Numbering::Short(short.to_string())
+        }
+        _ => Numbering::Numbered,
+    };
+    let (title, after) = group(stmt, skip_whitespace(stmt, pos))?;
+    match after == stmt.len() {
+        true => Some((name, title, numbering)),
+        false => None,
+    }
+}
+
+fn heading_element(name: &str, title: &str, label: Option<String>, numbering: Numbering) -> Element {
+    let t = title.to_string();
+    match name {
+        "part" => Element::from(Part(t, Vec::new(), label, numbering)),
+        "chapter" => Element::from(Chapter(t, Vec::new(), label, numbering)),
+        "section" => Element::from(Section(t, Vec::new(), label, numbering)),
+        "subsection" => Element::from(Subsection(t, Vec::new(), label, numbering)),
+        "subsubsection" => Element::from(Subsubsection(t, Vec::new(), label, numbering)),
+        "paragraph" => Element::from(Paragraph(t, Vec::new(), label, numbering)),
+        _ => Element::from(Subparagraph(t, Vec::new(), label, numbering)),
+    }
+}
+
+/// Reads the title, abstract, lists and book matter at the start of the body,
+/// returning where the elements start
+fn parse_front(body: &str, front: &mut FrontMatter) -> usize {
+    let mut pos = skip_whitespace(body, 0);
+    while pos < body.len() {
+        let rest = &body[pos..];
+        if rest.starts_with("\\begin{abstract}") || rest.starts_with("\\begin{frame}") {
+            let name = match rest.starts_with("\\begin{abstract}") {
+                true => "abstract",
+                false => "frame",
+            };
+            let after = pos + format!("\\begin{{{}}}", name).len();
+            let (inner_end, end) = match environment_end(body, name, after) {
+                Some(e) => e,
+                None => break,
+            };
+            let inner = body[after..inner_end].trim();
+            match (name, inner) {
+                ("abstract", text) => front.abstract_text = Some(text.to_string()),
+                (_, "\\titlepage") => front.title = true,
+                (_, "\\tableofcontents") => front.table_of_contents = true,
+                (_, "\\listoffigures") => front.list_of_figures = true,
+                (_, "\\listoftables") => front.list_of_tables = true,
+                _ => break,
+            }
+            pos = skip_whitespace(body, end);
+            continue;
+        }
+        let end = statement_end(body, pos);
+        let (name, args) = command(body[pos..end].trim_end());
+        match (name, args.as_slice()) {
+            ("frontmatter", []) => front.book_matter = true,
+            ("mainmatter", []) if front.book_matter => {
+                return skip_whitespace(body, end);
+            }
+            ("maketitle", []) => front.title = true,
+            ("tableofcontents", []) => front.table_of_contents = true,
+            ("listoffigures", []) => front.list_of_figures = true,
+            ("listoftables", []) => front.list_of_tables = true,
+            ("newpage", []) => front.new_page = true,
+            _ => break,
+        }
+        pos = skip_whitespace(body, end);
+    }
+    pos.min(body.len())
+}
+
+/// Parses `\begin{name}...\end{name}` starting at `pos`
+fn environment(body: &str, pos: usize) -> (Option<Element>, usize) {
+    let (name, after) = match group(body, pos + "\\begin".len()) {
+        Some(g) => g,
+        None => {
+            let end = statement_end(body, pos);
+            let stmt = body[pos..end].trim_end();
+            return (Some(Element::from(UserDefined::new(stmt, Level::Body))), end);
+        }
+    };
+    let (inner_end, end) = match environment_end(body, name, after) {
+        Some(e) => e,
+        None => {
+            let stmt = body[pos..].trim_end();
+            return (Some(Element::from(UserDefined::new(stmt, Level::Body))), body.len());
+        }
+    };
+    let raw = Element::from(UserDefined::new(&body[pos..end], Level::Body));
+    let header_end = body[after..inner_end].find('\n').map_or(inner_end, |i| after + i);
+    let (header, label) = split_label(body[after..header_end].trim());
+    // Anything after the options and arguments can't be kept by Environment
+    let (options, arguments) = match header_arguments(header) {
+        Some(h) => h,
+        None => return (Some(raw), end),
+    };
+    let inner = &body[header_end..inner_end];
+    let element = match name {
+        "itemize" | "enumerate" | "description" if header.is_empty() && label.is_none() => {
+            let mode = match name {
+                "itemize" => ListMode::Itemize,
+                "description" => ListMode::Description,
+                _ => ListMode::Enumerate,
+            };
+            match items(inner) {
+                Some(items) => Element::from(List::raw(mode, &items)),
+                None => raw,
+            }
+        }
+        "itemize" | "enumerate" | "description" => raw,
+        _ => {
+            let mut env = Environment::new(name);
+            env.2 = label;
+            env.3 = options;
+            env.4 = arguments;
+            for line in inner.trim_matches(|c| c == '\n' || c == '\r').lines() {
+                env.attach_string(line.to_string());
+            }
+            Element::from(env)
+        }
+    };
+    (Some(element), end)
+}
+
+/// Reads the `[...]` options and `{...}` arguments of an environment header,
+/// `None` when there is anything else
+fn header_arguments(header: &str) -> Option<(Option<String>, Vec<String>)> {
+    let (options, mut pos) = match bracket(header, 0) {
+        Some((o, end)) => (Some(o.to_string()), end),
+        None => (None, 0),
+    };
+    let mut arguments = Vec::new();
+    while let Some((a, end)) = group(header, pos) {
+        arguments.push(a.to_string());
+        pos = end;
+    }
+    match pos == header.len() {
+        true => Some((options, arguments)),
+        false => None,
+    }
+}
+
+/// Splits the inside of a list at its top level `\item`s
+fn items(inner: &str) -> Option<Vec<Item>> {
+    let bytes = inner.as_bytes();
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            let rest = &inner[i..];
+            if rest.starts_with("\\begin{") {
+                depth += 1;
+            } else if rest.starts_with("\\end{") {
+                depth -= 1;
+            } else if depth == 0 && rest.starts_with("\\item") && !starts_with_letter(&rest[5..]) {
+                match start {
+                    Some(s) => items.push(inner[s..i].trim().to_string()),
+                    None if !inner[..i].trim().is_empty() => return None,
+                    None => {}
+                }
+                start = Some(i + "\\item".len());
+            }
+            i += 1;
+        }
+        i += 1;
+    }
+    match start {
+        Some(s) => items.push(inner[s..].trim().to_string()),
+        None if !inner.trim().is_empty() => return None,
+        None => {}
+    }
+    Some(items)
+}
+
+/// Finds the matching `\end{name}`, returning where the inside ends
+/// and where the environment ends
+fn environment_end(s: &str, name: &str, from: usize) -> Option<(usize, usize)> {
+    let begin = format!("\\begin{{{}}}", name);
+    let end = format!("\\end{{{}}}", name);
+    let mut depth = 0;
+    let mut i = from;
+    while let Some(next) = s[i..].find('\\') {
+        i += next;
+        let rest = &s[i..];
+        if rest.starts_with(&begin) {
+            depth += 1;
+            i += begin.len();
+        } else if rest.starts_with(&end) {
+            if depth == 0 {
+                return Some((i, i + end.len()));
+            }
+            depth -= 1;
+            i += end.len();
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Splits a statement into its command name and the `[...]`/`{...}` arguments
+/// that directly follow it, the name is empty if anything else follows
+fn command(stmt: &str) -> (&str, Vec<String>) {
+    let rest = match stmt.strip_prefix('\\') {
+        Some(r) => r,
+        None => return ("", Vec::new()),
+    };
+    let name_len = rest
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    let mut args = Vec::new();
+    let mut pos = 1 + name_len;
+    loop {
+        let next = skip_whitespace(stmt, pos);
+        match group(stmt, next).or_else(|| bracket(stmt, next)) {
+            Some((arg, after)) => {
+                args.push(arg.to_string());
+                pos = after;
+            }
+            None => break,
+        }
+    }
+    if !stmt[pos..].trim().is_empty() {
+        return ("", Vec::new());
+    }
+    (&rest[..name_len], args)
+}
+
+/// Splits a trailing `\label{...}` off a statement
+fn split_label(stmt: &str) -> (&str, Option<String>) {
+    if let Some(i) = stmt.rfind("\\label{") {
+        if let Some((label, after)) = group(stmt, i + "\\label".len()) {
+            if after == stmt.len() {
+                return (stmt[..i].trim_end(), Some(label.to_string()));
+            }
+        }
+    }
+    (stmt, None)
+}
+
+/// Checks that the command only takes mandatory arguments (`\name{...}`)
+fn is_group(stmt: &str, name: &str) -> bool {
+    stmt[1 + name.len()..].trim_start().starts_with('{')
+}
+
+/// Reads a balanced `{...}` group at `start`, returns the inside and the position after it
+fn group(s: &str, start: usize) -> Option<(&str, usize)> {
+    delimited(s, start, b'{', b'}')
+}
+
+/// Reads a `[...]` option at `start`, returns the inside and the position after it
+fn bracket(s: &str, start: usize) -> Option<(&str, usize)> {
+    delimited(s, start, b'[', b']')
+}
+
+fn delimited(s: &str, start: usize, open: u8, close: u8) -> Option<(&str, usize)> {
+    let bytes = s.as_bytes();
+    if bytes.get(start) != Some(&open) {
+        return None;
+    }
+    let mut depth = 0;
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'{' => depth += 1,
+            b'}' if depth > 0 => depth -= 1,
+            c if c == close && depth == 0 => return Some((&s[start + 1..i], i + 1)),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Finds the end of a statement: the end of its line, or of the line where
+/// its braces are balanced again
+fn statement_end(s: &str, start: usize) -> usize {
+    let bytes = s.as_bytes();
+    let mut depth = 0;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'{' => depth += 1,
+            b'}' => depth -= 1,
+            b'%' => {
+                i = s[i..].find('\n').map_or(s.len(), |n| i + n);
+                continue;
+            }
+            b'\n' if depth <= 0 => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    s.len()
+}
+
+/// Finds the end of a text paragraph: a blank line or a line starting a
+/// comment, environment or another structural command
+fn paragraph_end(s: &str, start: usize) -> usize {
+    let mut pos = start;
+    loop {
+        let line_end = match s[pos..].find('\n') {
+            Some(i) => pos + i,
+            None => return s.len(),
+        };
+        let next = s[line_end + 1..].trim_start_matches([' ', '\t']);
+        let breaks = next.is_empty()
+            || next.starts_with('\n')
+            || next.starts_with('%')
+            || next.starts_with("\\begin{")
+            || next.starts_with("\\input{")
+            || is_par(next)
+            || HEADINGS.iter().any(|h| {
+                ["{", "*", "["]
+                    .iter()
+                    .any(|c| next.starts_with(&format!("\\{}{}", h, c)))
+            });
+        if breaks {
+            return line_end;
+        }
+        pos = line_end + 1;
+    }
+}
+
+fn skip_whitespace(s: &str, start: usize) -> usize {
+    s[start..]
+        .find(|c: char| !c.is_whitespace())
+        .map_or(s.len(), |i| start + i)
+}
+
+/// Checks for a `\par` command, but not `\paragraph` or `\parskip`
+fn is_par(s: &str) -> bool {
+    s.starts_with("\\par") && !starts_with_letter(&s[4..])
+}
+
+fn starts_with_letter(s: &str) -> bool {
+    s.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+}
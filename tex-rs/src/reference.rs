@@ -0,0 +1,213 @@
+use crate::Convert;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// Commands that refer to a label
+const REFERENCE_COMMANDS: [&str; 6] = ["ref", "pageref", "eqref", "autoref", "cref", "Cref"];
+
+/// Environments whose content is written as is and not read as commands,
+/// beamer frames need to be fragile for them
+pub(crate) const VERBATIM_ENVIRONMENTS: [&str; 5] =
+    ["verbatim", "verbatim*", "Verbatim", "lstlisting", "minted"];
+
+/// Ref struct with the kind of reference and the label it points at,
+/// used inline in text, cells and captions
+#[derive(Debug, Clone)]
+pub struct Ref(pub RefKind, pub String);
+
+/// Kinds of references
+/// - Ref `(\ref{...})`
+/// - PageRef `(\pageref{...})`
+/// - EqRef `(\eqref{...})`, needs `amsmath`
+/// - AutoRef `(\autoref{...})`, needs `hyperref`
+#[derive(Debug, Clone)]
+pub enum RefKind {
+    Ref,
+    PageRef,
+    EqRef,
+    AutoRef,
+}
+
+/// Problems found when checking the labels and references of a document
+/// - Undefined: a reference to a label that does not exist
+/// - Duplicate: a label defined more than once
+/// - Unnumbered: a label on an unnumbered heading, equation or align, or on a
+///   figure without a caption, which is not written since there is no number
+///   to refer to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceProblem {
+    Undefined(String),
+    Duplicate(String),
+    Unnumbered(String),
+}
+
+// Implementations //
+impl Ref {
+    pub fn new(kind: RefKind, label: &str) -> Self {
+        Self(kind, label.to_string())
+    }
+}
+
+/// Finds the labels defined in LaTeX source, in order
+pub fn labels(source: &str) -> Vec<String> {
+    arguments(source, "label")
+}
+
+/// Finds the labels referred to in LaTeX source, in order
+pub fn references(source: &str) -> Vec<String> {
+    let mut v = Vec::new();
+    for cmd in REFERENCE_COMMANDS {
+        for arg in arguments(source, cmd) {
+            // cleveref takes a list of labels
+            v.extend(arg.split(',').map(|l| l.trim().to_string()));
+        }
+    }
+    v
+}
+
+/// Checks that every reference has a label and that no label is defined twice,
+/// `extra_labels` are labels defined outside the source, like in `\input` files
+pub fn check_references(source: &str, extra_labels: &[String]) -> Vec<ReferenceProblem> {
+    let mut problems = Vec::new();
+    let mut seen: Vec<String> = Vec::new();
+    let defined = labels(source);
+    for label in defined.iter().chain(extra_labels) {
+        if seen.contains(label) {
+            let problem = ReferenceProblem::Duplicate(label.clone());
+            if !problems.contains(&problem) {
+                problems.push(problem);
+            }
+        } else {
+            seen.push(label.clone());
+        }
+    }
+    for reference in references(source) {
+        let problem = ReferenceProblem::Undefined(reference.clone());
+        if !seen.contains(&reference) && !problems.contains(&problem) {
+            problems.push(problem);
+        }
+    }
+    problems
+}
+
+/// Packages needed by the references in LaTeX source written as is
+pub fn reference_packages(source: &str) -> Vec<String> {
+    let mut v = Vec::new();
+    if !arguments(source, "eqref").is_empty() {
+        v.push("amsmath".to_string());
+    }
+    if !arguments(source, "autoref").is_empty() {
+        v.push("hyperref".to_string());
+    }
+    v
+}
+
+/// Finds the `{...}` argument of every `\cmd{...}` in source, skipping
+/// optional arguments like the note in `\cite[p. 2]{...}`, comments, verbatim
+/// text and macro parameters like the `#1` of `\newcommand`
+pub(crate) fn arguments(source: &str, cmd: &str) -> Vec<String> {
+    let pattern = format!("\\{}", cmd);
+    let source = strip_ignored(source);
+    let mut v = Vec::new();
+    let mut rest = source.as_str();
+    while let Some(i) = rest.find(&pattern) {
+        rest = &rest[i + pattern.len()..];
+        let mut arg = rest;
+        while let Some(after) = arg.strip_prefix('[') {
+            match after.find(']') {
+                Some(end) => arg = &after[end + 1..],
+                None => break,
+            }
+        }
+        // Anything else is a longer command like `\citet` for `\cite`
+        if let Some(after) = arg.strip_prefix('{') {
+            if let Some(end) = after.find('}') {
+                if !after[..end].contains('#') {
+                    v.push(after[..end].to_string());
+                }
+                rest = &after[end..];
+            }
+        }
+    }
+    v
+}
+
+/// Source without `%` comments, `\verb` and the verbatim environments,
+/// which are not read by LaTeX as commands
+fn strip_ignored(source: &str) -> String {
+    let mut s = String::new();
+    let mut rest = source;
+    while let Some(c) = rest.chars().next() {
+        if let Some(env) = VERBATIM_ENVIRONMENTS
+            .iter()
+            .find(|e| rest.starts_with(&format!("\\begin{{{}}}", e)))
+        {
+            let end = format!("\\end{{{}}}", env);
+            match rest.find(&end) {
+                Some(i) => rest = &rest[i + end.len()..],
+                None => rest = "",
+            }
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("\\verb") {
+            // `\verb` is followed by a delimiter, or `*` and a delimiter
+            let after = after.strip_prefix('*').unwrap_or(after);
+            if let Some(d) = after.chars().next().filter(|d| !d.is_alphabetic()) {
+                let after = &after[d.len_utf8()..];
+                match after.find(d) {
+                    Some(i) => rest = &after[i + d.len_utf8()..],
+                    None => rest = "",
+                }
+                continue;
+            }
+        }
+        match c {
+            '\\' => {
+                // Keeps escaped characters like `\%` together
+                let len = rest[1..].chars().next().map_or(0, |n| n.len_utf8());
+                s.push_str(&rest[..1 + len]);
+                rest = &rest[1 + len..];
+            }
+            '%' => match rest.find('\n') {
+                Some(i) => rest = &rest[i..],
+                None => rest = "",
+            },
+            c => {
+                s.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    s
+}
+
+// Trait Implementations //
+impl Convert for Ref {
+    fn to_latex_string(&self) -> String {
+        let cmd = match self.0 {
+            RefKind::Ref => "ref",
+            RefKind::PageRef => "pageref",
+            RefKind::EqRef => "eqref",
+            RefKind::AutoRef => "autoref",
+        };
+        format!("\\{}{{{}}}", cmd, &self.1)
+    }
+    /// Packages needed by the kind of reference
+    fn packages(&self) -> Vec<String> {
+        match self.0 {
+            RefKind::EqRef => vec!["amsmath".to_string()],
+            RefKind::AutoRef => vec!["hyperref".to_string()],
+            RefKind::Ref | RefKind::PageRef => Vec::new(),
+        }
+    }
+}
+
+impl Display for ReferenceProblem {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ReferenceProblem::Undefined(l) => write!(f, "undefined reference `{}`", l),
+            ReferenceProblem::Duplicate(l) => write!(f, "duplicate label `{}`", l),
+            ReferenceProblem::Unnumbered(l) => write!(f, "label `{}` on an unnumbered element", l),
+        }
+    }
+}
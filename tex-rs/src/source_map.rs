@@ -0,0 +1,91 @@
+use crate::element::Element;
+use crate::log::Diagnostic;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::path::{Component, Path};
+
+/// Path of an element in the document tree, as each element's name and its
+/// index in its parent, displayed like `part 2 / chapter 1 / text 3`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ElementPath(pub Vec<(&'static str, usize)>);
+
+/// Range of output lines (1-based and inclusive) written by an element
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    /// First line written by the element
+    pub start: usize,
+    /// Last line written by the element
+    pub end: usize,
+    /// The element that wrote the lines
+    pub path: ElementPath,
+}
+
+/// Source map from lines of a written document back to the elements that
+/// produced them, in the order they were written
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SourceMap(pub Vec<Mapping>);
+
+// Implementations //
+impl ElementPath {
+    /// Path of the `index`th element of `document_elements`
+    pub fn root(element: &Element, index: usize) -> Self {
+        Self(vec![(element.name(), index)])
+    }
+    /// Path of the `index`th child of this path
+    pub fn child(&self, element: &Element, index: usize) -> Self {
+        let mut path = self.0.clone();
+        path.push((element.name(), index));
+        Self(path)
+    }
+    /// Finds the element this path points to
+    pub fn get<'a>(&self, elements: &'a [Element]) -> Option<&'a Element> {
+        let ((_, first), rest) = self.0.split_first()?;
+        let mut element = elements.get(*first)?;
+        for (_, i) in rest {
+            element = element.children()?.get(*i)?;
+        }
+        Some(element)
+    }
+}
+
+impl SourceMap {
+    /// Records that lines `start..=end` were written by the element at `path`
+    pub fn add(&mut self, start: usize, end: usize, path: ElementPath) {
+        self.0.push(Mapping { start, end, path });
+    }
+    /// Finds the element that wrote a line
+    pub fn lookup(&self, line: usize) -> Option<&ElementPath> {
+        self.0
+            .iter()
+            .find(|m| m.start <= line && line <= m.end)
+            .map(|m| &m.path)
+    }
+    /// Finds the element a diagnostic from the compiler log points at, when
+    /// it is in `main`, the file the document was written to, like `main.tex`
+    /// for `Latex::compile`
+    /// Diagnostics from other files, like an `\input` file or a package, are
+    /// not in the map and give `None`
+    pub fn locate(&self, diagnostic: &Diagnostic, main: &Path) -> Option<&ElementPath> {
+        let file = Path::new(diagnostic.file.as_ref()?);
+        match normal(file).eq(normal(main)) {
+            true => self.lookup(diagnostic.line?),
+            false => None,
+        }
+    }
+}
+
+/// Components of a path without the `.` ones, so `./main.tex` is `main.tex`
+fn normal(path: &Path) -> impl Iterator<Item = Component<'_>> {
+    path.components().filter(|c| *c != Component::CurDir)
+}
+
+impl Display for ElementPath {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let steps: Vec<String> = self
+            .0
+            .iter()
+            .map(|(name, i)| format!("{} {}", name, i + 1))
+            .collect();
+        write!(f, "{}", steps.join(" / "))
+    }
+}
@@ -0,0 +1,216 @@
+use crate::bibliography::Cite;
+use crate::element::{escape, verb, Raw};
+use crate::hyperref::{escape_url, Link, Url};
+use crate::math::{Expr, Math};
+use crate::reference::Ref;
+use crate::style::Style;
+use crate::Convert;
+
+/// Paragraph of inline spans, written on one line, as an element it ends
+/// with a `\par` and used inline, like in a caption or list item, it doesn't
+#[derive(Debug, Clone)]
+pub struct RichText(pub Vec<Span>);
+
+/// Inline span of a paragraph, styles contain spans so they can be nested
+/// - Plain text, already escaped
+/// - Bold `(\textbf{...})`
+/// - Italic `(\textit{...})`
+/// - Emph `(\emph{...})`
+/// - Monospace `(\texttt{...})`
+/// - SmallCaps `(\textsc{...})`
+/// - Verbatim text as is `(\verb!...!)`, or `(\texttt{...})` with the text
+///   escaped when it is inside another span, where `\verb` can't be
+/// - Link with its url `(\href{...}{...})`
+/// - Url shown as is `(\url{...})`
+/// - Footnote `(\footnote{...})`
+/// - Math `(\(...\))`
+/// - Cite `(\cite{...})`
+/// - Ref `(\ref{...})`
+/// - Styled, with fonts, sizes and colors combined
+#[derive(Debug, Clone)]
+pub enum Span {
+    Plain(String),
+    Bold(Vec<Span>),
+    Italic(Vec<Span>),
+    Emph(Vec<Span>),
+    Monospace(Vec<Span>),
+    SmallCaps(Vec<Span>),
+    Verbatim(String),
+    Link(String, Vec<Span>),
+    Url(String),
+    Footnote(Vec<Span>),
+    Math(Expr),
+    Cite(Cite),
+    Ref(Ref),
+    Styled(Style, Vec<Span>),
+}
+
+// Implementations //
+impl RichText {
+    pub fn new(spans: Vec<Span>) -> Self {
+        Self(spans)
+    }
+    /// Adds a span, strings are escaped unless they are `Raw`
+    pub fn add(&mut self, span: impl Into<Span>) {
+        self.0.push(span.into());
+    }
+}
+
+impl Span {
+    /// Plain text, escaping it unless it is `Raw`
+    pub fn text(text: impl Convert) -> Self {
+        Span::Plain(text.to_latex_string())
+    }
+    pub fn bold(span: impl Into<Span>) -> Self {
+        Span::Bold(vec![span.into()])
+    }
+    pub fn italic(span: impl Into<Span>) -> Self {
+        Span::Italic(vec![span.into()])
+    }
+    pub fn emph(span: impl Into<Span>) -> Self {
+        Span::Emph(vec![span.into()])
+    }
+    pub fn monospace(span: impl Into<Span>) -> Self {
+        Span::Monospace(vec![span.into()])
+    }
+    pub fn small_caps(span: impl Into<Span>) -> Self {
+        Span::SmallCaps(vec![span.into()])
+    }
+    /// Verbatim text, written as is
+    pub fn verbatim(text: &str) -> Self {
+        Span::Verbatim(text.to_string())
+    }
+    /// Link to a url, the url is escaped with `escape_url`
+    pub fn link(url: &str, span: impl Into<Span>) -> Self {
+        Span::Link(escape_url(url), vec![span.into()])
+    }
+    /// Url shown as is, the url is escaped with `escape_url`
+    pub fn url(url: &str) -> Self {
+        Span::Url(escape_url(url))
+    }
+    pub fn footnote(span: impl Into<Span>) -> Self {
+        Span::Footnote(vec![span.into()])
+    }
+    pub fn styled(style: Style, span: impl Into<Span>) -> Self {
+        Span::Styled(style, vec![span.into()])
+    }
+    /// Span inside the argument of a command, where verbatim text is
+    /// written with `\texttt` instead of `\verb`
+    fn argument_string(&self) -> String {
+        match self {
+            Span::Verbatim(s) => format!("\\texttt{{{}}}", escape(s)),
+            _ => self.to_latex_string(),
+        }
+    }
+}
+
+/// Spans written one after the other inside the argument of a command
+fn spans(v: &[Span]) -> String {
+    v.iter().map(|s| s.argument_string()).collect()
+}
+
+// Trait Implementations //
+impl Convert for Span {
+    fn to_latex_string(&self) -> String {
+        match self {
+            Span::Plain(s) => s.clone(),
+            Span::Verbatim(s) => verb(s),
+            Span::Bold(v) => format!("\\textbf{{{}}}", spans(v)),
+            Span::Italic(v) => format!("\\textit{{{}}}", spans(v)),
+            Span::Emph(v) => format!("\\emph{{{}}}", spans(v)),
+            Span::Monospace(v) => format!("\\texttt{{{}}}", spans(v)),
+            Span::SmallCaps(v) => format!("\\textsc{{{}}}", spans(v)),
+            Span::Link(url, v) => format!("\\href{{{}}}{{{}}}", url, spans(v)),
+            Span::Url(url) => format!("\\url{{{}}}", url),
+            Span::Footnote(v) => format!("\\footnote{{{}}}", spans(v)),
+            Span::Math(e) => format!("\\({}\\)", e.to_latex_string()),
+            Span::Cite(c) => c.to_latex_string(),
+            Span::Ref(r) => r.to_latex_string(),
+            Span::Styled(style, v) => style.apply(&spans(v)),
+        }
+    }
+    /// Packages needed by the span and the spans inside it
+    fn packages(&self) -> Vec<String> {
+        let (mut v, inner) = match self {
+            Span::Link(_, v) => (vec!["hyperref".to_string()], v),
+            Span::Styled(style, v) => (style.packages(), v),
+            Span::Bold(v)
+            | Span::Italic(v)
+            | Span::Emph(v)
+            | Span::Monospace(v)
+            | Span::SmallCaps(v)
+            | Span::Footnote(v) => (Vec::new(), v),
+            Span::Url(_) => return vec!["hyperref".to_string()],
+            Span::Math(e) => return e.packages(),
+            Span::Ref(r) => return r.packages(),
+            Span::Cite(c) => return c.packages(),
+            Span::Plain(_) | Span::Verbatim(_) => return Vec::new(),
+        };
+        v.extend(inner.iter().flat_map(|s| s.packages()));
+        v
+    }
+}
+
+impl Convert for RichText {
+    fn to_latex_string(&self) -> String {
+        self.0.iter().map(|s| s.to_latex_string()).collect()
+    }
+    /// Packages needed by the spans, like `hyperref` for links and `xcolor` for colors
+    fn packages(&self) -> Vec<String> {
+        self.0.iter().flat_map(|s| s.packages()).collect()
+    }
+}
+
+impl From<&str> for Span {
+    fn from(s: &str) -> Self {
+        Span::text(s)
+    }
+}
+
+impl From<String> for Span {
+    fn from(s: String) -> Self {
+        Span::text(s)
+    }
+}
+
+impl From<Raw> for Span {
+    fn from(r: Raw) -> Self {
+        Span::Plain(r.0)
+    }
+}
+
+impl From<Expr> for Span {
+    fn from(e: Expr) -> Self {
+        Span::Math(e)
+    }
+}
+
+impl From<Math> for Span {
+    fn from(m: Math) -> Self {
+        Span::Math(m.0)
+    }
+}
+
+impl From<Cite> for Span {
+    fn from(c: Cite) -> Self {
+        Span::Cite(c)
+    }
+}
+
+impl From<Link> for Span {
+    fn from(l: Link) -> Self {
+        Span::Link(l.0, vec![Span::Plain(l.1)])
+    }
+}
+
+impl From<Url> for Span {
+    fn from(u: Url) -> Self {
+        Span::Url(u.0)
+    }
+}
+
+impl From<Ref> for Span {
+    fn from(r: Ref) -> Self {
+        Span::Ref(r)
+    }
+}
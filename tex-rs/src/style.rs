@@ -0,0 +1,183 @@
+use crate::element::TextType;
+use crate::Convert;
+
+/// Style that combines text types, a font size and `xcolor` colors,
+/// like bold and italic in large red text
+#[derive(Debug, Clone, Default)]
+pub struct Style {
+    /// Text types applied inside out, `Normal` and `Verbatim` are skipped
+    pub types: Vec<TextType>,
+    pub size: Option<FontSize>,
+    /// Foreground color `(\textcolor{...}{...})`
+    pub color: Option<Color>,
+    /// Background color `(\colorbox{...}{...})`
+    pub background: Option<Color>,
+}
+
+/// Font sizes from smallest to largest
+/// - Tiny `(\tiny)`
+/// - ScriptSize `(\scriptsize)`
+/// - FootnoteSize `(\footnotesize)`
+/// - Small `(\small)`
+/// - NormalSize `(\normalsize)`
+/// - Large `(\large)`
+/// - Larger `(\Large)`
+/// - Largest `(\LARGE)`
+/// - Huge `(\huge)`
+/// - Huger `(\Huge)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FontSize {
+    Tiny,
+    ScriptSize,
+    FootnoteSize,
+    Small,
+    NormalSize,
+    Large,
+    Larger,
+    Largest,
+    Huge,
+    Huger,
+}
+
+/// Colors of `xcolor`
+/// - Named, like `red`, `blue!50` or a color defined with `Latex::define_color`
+/// - Rgb with values from 0 to 255 `([RGB]{...})`
+/// - Html hex code without the `#`, like `FF8800` `([HTML]{...})`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Color {
+    Named(String),
+    Rgb(u8, u8, u8),
+    Html(String),
+}
+
+// Implementations //
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adds a text type, like `Bold`
+    pub fn add(&mut self, text_type: TextType) {
+        self.types.push(text_type);
+    }
+    pub fn set_size(&mut self, size: FontSize) {
+        self.size = Some(size);
+    }
+    pub fn set_color(&mut self, color: Color) {
+        self.color = Some(color);
+    }
+    pub fn set_background(&mut self, color: Color) {
+        self.background = Some(color);
+    }
+    /// Checks if the style changes anything
+    pub fn is_empty(&self) -> bool {
+        self.types.iter().all(|t| t.command().is_none())
+            && self.size.is_none()
+            && self.color.is_none()
+            && self.background.is_none()
+    }
+    /// Applies the style to LaTeX, text should already be escaped
+    pub fn apply(&self, text: &str) -> String {
+        let mut s = text.to_string();
+        for cmd in self.types.iter().filter_map(|t| t.command()) {
+            s = format!("\\{}{{{}}}", cmd, s);
+        }
+        if let Some(size) = self.size {
+            s = format!("{{{} {}}}", size.to_latex_string(), s);
+        }
+        if let Some(c) = &self.color {
+            s = format!("\\textcolor{}{{{}}}", c.to_latex_string(), s);
+        }
+        if let Some(c) = &self.background {
+            s = format!("\\colorbox{}{{{}}}", c.to_latex_string(), s);
+        }
+        s
+    }
+    /// Applies the parts of the style that are declarations, the size and
+    /// the color, in a group `({\large\color{...} ...})`, used for verbatim
+    /// text that can't be inside the argument of a command
+    pub fn apply_declarations(&self, text: &str) -> String {
+        let mut declarations = String::new();
+        if let Some(size) = self.size {
+            declarations.push_str(&size.to_latex_string());
+        }
+        if let Some(c) = &self.color {
+            declarations.push_str(&format!("\\color{}", c.to_latex_string()));
+        }
+        match declarations.is_empty() {
+            true => text.to_string(),
+            false => format!("{{{} {}}}", declarations, text),
+        }
+    }
+    /// Packages needed by the style, `xcolor` for colors
+    pub fn packages(&self) -> Vec<String> {
+        match self.color.is_some() || self.background.is_some() {
+            true => vec!["xcolor".to_string()],
+            false => Vec::new(),
+        }
+    }
+    /// Packages needed by `apply_declarations`, `xcolor` for the color
+    pub fn declaration_packages(&self) -> Vec<String> {
+        match self.color.is_some() {
+            true => vec!["xcolor".to_string()],
+            false => Vec::new(),
+        }
+    }
+}
+
+impl Color {
+    /// Named color, like `red`
+    pub fn named(name: &str) -> Self {
+        Color::Named(name.to_string())
+    }
+    /// Html color, a leading `#` is removed
+    pub fn html(code: &str) -> Self {
+        Color::Html(code.trim_start_matches('#').to_uppercase())
+    }
+    /// `\definecolor` or `\colorlet` that defines the color under a name
+    pub fn definition(&self, name: &str) -> String {
+        match self {
+            Color::Named(c) => format!("\\colorlet{{{}}}{{{}}}", name, c),
+            Color::Rgb(r, g, b) => format!("\\definecolor{{{}}}{{RGB}}{{{},{},{}}}", name, r, g, b),
+            Color::Html(c) => format!("\\definecolor{{{}}}{{HTML}}{{{}}}", name, c),
+        }
+    }
+}
+
+// Trait Implementations //
+impl Convert for FontSize {
+    fn to_latex_string(&self) -> String {
+        let cmd = match self {
+            FontSize::Tiny => "tiny",
+            FontSize::ScriptSize => "scriptsize",
+            FontSize::FootnoteSize => "footnotesize",
+            FontSize::Small => "small",
+            FontSize::NormalSize => "normalsize",
+            FontSize::Large => "large",
+            FontSize::Larger => "Large",
+            FontSize::Largest => "LARGE",
+            FontSize::Huge => "huge",
+            FontSize::Huger => "Huge",
+        };
+        format!("\\{}", cmd)
+    }
+}
+
+/// Color as the arguments of `\textcolor` and `\colorbox`, like `[RGB]{255,0,0}`
+impl Convert for Color {
+    fn to_latex_string(&self) -> String {
+        match self {
+            Color::Named(c) => format!("{{{}}}", c),
+            Color::Rgb(r, g, b) => format!("[RGB]{{{},{},{}}}", r, g, b),
+            Color::Html(c) => format!("[HTML]{{{}}}", c),
+        }
+    }
+}
+
+impl From<TextType> for Style {
+    fn from(text_type: TextType) -> Self {
+        Self {
+            types: vec![text_type],
+            ..Self::default()
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use crate::Convert;
+
+/// Table struct with the column specs, header and body rows, and the
+/// environment used to write it
+#[derive(Debug, Clone)]
+pub struct Table {
+    /// Environment the table is written in
+    pub mode: TableMode,
+    /// Column specs `(\begin{tabular}{...})`
+    pub columns: Vec<Column>,
+    /// Header rows, repeated on every page by `longtable`
+    pub header: Vec<Row>,
+    /// Body rows
+    pub rows: Vec<Row>,
+    /// Rules drawn above and below the header and at the bottom
+    pub style: TableStyle,
+    /// `\caption{...}`, already escaped
+    pub caption: Option<String>,
+    /// Packages needed by the typed parts of the caption, like `amsmath` for `Math`
+    pub caption_packages: Vec<String>,
+    /// `\label{...}`
+    pub label: Option<String>,
+    /// Float placement `(\begin{table}[...])`
+    pub placement: Option<String>,
+}
+
+/// Single cell, spanning one or more columns and rows
+#[derive(Debug, Clone)]
+pub struct Cell {
+    /// Content of the cell, already escaped
+    pub content: String,
+    /// Packages needed by the typed content, like `amsmath` for `Math`
+    pub packages: Vec<String>,
+    /// Number of columns spanned `(\multicolumn{n}{...}{...})`
+    pub columns: usize,
+    /// Number of rows spanned `(\multirow{n}{*}{...})`
+    pub rows: usize,
+    /// Alignment of a multicolumn cell
+    pub align: Column,
+}
+
+/// Environments a table can be written in
+/// - Tabular `(\begin{tabular}...)`
+/// - Tabularx with the table width `(\begin{tabularx}{width}...)`
+/// - Longtable, which breaks across pages `(\begin{longtable}...)`
+#[derive(Debug, Clone)]
+pub enum TableMode {
+    Tabular,
+    Tabularx(String),
+    Longtable,
+}
+
+/// Column specs
+/// - Left `(l)`
+/// - Center `(c)`
+/// - Right `(r)`
+/// - Paragraph with a width `(p{...})`
+/// - X, a stretched column for tabularx `(X)`
+/// - Rule, a vertical line `(|)`
+/// - Custom spec
+#[derive(Debug, Clone)]
+pub enum Column {
+    Left,
+    Center,
+    Right,
+    Paragraph(String),
+    X,
+    Rule,
+    Custom(String),
+}
+
+/// Rows of a table, either cells or a horizontal rule
+#[derive(Debug, Clone)]
+pub enum Row {
+    Cells(Vec<Cell>),
+    Rule(Rule),
+}
+
+/// Horizontal rules
+/// - Line across the table `(\hline or \midrule)`
+/// - Partial line from one column to another, counting from 1
+///   `(\cline{a-b} or \cmidrule{a-b})`
+#[derive(Debug, Clone)]
+pub enum Rule {
+    Line,
+    Partial(usize, usize),
+}
+
+/// Rules drawn around the header and at the top and bottom
+/// - Plain: no rules
+/// - Hline: `\hline`
+/// - Booktabs: `\toprule`, `\midrule` and `\bottomrule`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableStyle {
+    Plain,
+    Hline,
+    Booktabs,
+}
+
+// Implementations //
+impl Table {
+    pub fn new(mode: TableMode, columns: &[Column]) -> Self {
+        Self {
+            mode,
+            columns: columns.to_owned(),
+            header: Vec::new(),
+            rows: Vec::new(),
+            style: TableStyle::Booktabs,
+            caption: None,
+            caption_packages: Vec::new(),
+            label: None,
+            placement: None,
+        }
+    }
+    /// Sets the rules drawn around the header and at the top and bottom
+    pub fn set_style(&mut self, style: TableStyle) {
+        self.style = style;
+    }
+    /// Sets the caption, escaping it unless it is `Raw`
+    pub fn set_caption(&mut self, caption: impl Convert) {
+        self.caption = Some(caption.to_latex_string());
+        self.caption_packages = caption.packages();
+    }
+    /// Sets the label used to reference the table, it is only written with a caption
+    pub fn set_label(&mut self, label: &str) {
+        self.label = Some(label.to_string());
+    }
+    /// The label when it is not written because there is no caption
+    pub fn unnumbered_labels(&self) -> Vec<String> {
+        match &self.caption {
+            Some(_) => Vec::new(),
+            None => self.label.iter().cloned().collect(),
+        }
+    }
+    /// Sets the float placement, like `htbp`
+    pub fn set_placement(&mut self, placement: &str) {
+        self.placement = Some(placement.to_string());
+    }
+    /// Adds a header row
+    pub fn add_header(&mut self, cells: Vec<Cell>) {
+        self.header.push(Row::Cells(cells));
+    }
+    /// Adds a body row
+    pub fn add_row(&mut self, cells: Vec<Cell>) {
+        self.rows.push(Row::Cells(cells));
+    }
+    /// Adds a rule after the last body row
+    pub fn add_rule(&mut self, rule: Rule) {
+        self.rows.push(Row::Rule(rule));
+    }
+    fn spec(&self) -> String {
+        self.columns.iter().map(|c| c.to_latex_string()).collect()
+    }
+    fn rule(&self, rule: &Rule) -> String {
+        let booktabs = self.style == TableStyle::Booktabs;
+        match (rule, booktabs) {
+            (Rule::Line, true) => "\\midrule".to_string(),
+            (Rule::Line, false) => "\\hline".to_string(),
+            (Rule::Partial(a, b), true) => format!("\\cmidrule{{{}-{}}}", a, b),
+            (Rule::Partial(a, b), false) => format!("\\cline{{{}-{}}}", a, b),
+        }
+    }
+    /// Rule for the top, below the header and the bottom
+    fn frame(&self, booktabs: &str) -> Option<String> {
+        match self.style {
+            TableStyle::Plain => None,
+            TableStyle::Hline => Some("\\hline".to_string()),
+            TableStyle::Booktabs => Some(booktabs.to_string()),
+        }
+    }
+    fn row(&self, row: &Row) -> String {
+        match row {
+            Row::Cells(cells) => {
+                let cells: Vec<String> = cells.iter().map(|c| c.to_latex_string()).collect();
+                format!("{} \\\\", cells.join(" & "))
+            }
+            Row::Rule(rule) => self.rule(rule),
+        }
+    }
+    /// Top rule, header rows and the rule below them
+    fn head(&self) -> Vec<String> {
+        let mut s = Vec::new();
+        s.extend(self.frame("\\toprule"));
+        if !self.header.is_empty() {
+            s.extend(self.header.iter().map(|r| self.row(r)));
+            s.extend(self.frame("\\midrule"));
+        }
+        s
+    }
+}
+
+impl Cell {
+    /// Creates a cell, escaping it unless it is `Raw`
+    pub fn new(content: impl Convert) -> Self {
+        Self {
+            content: content.to_latex_string(),
+            packages: content.packages(),
+            columns: 1,
+            rows: 1,
+            align: Column::Center,
+        }
+    }
+    /// Creates a cell spanning `columns` columns with its own alignment
+    pub fn multicolumn(content: impl Convert, columns: usize, align: Column) -> Self {
+        Self {
+            columns,
+            align,
+            ..Self::new(content)
+        }
+    }
+    /// Creates a cell spanning `rows` rows, the cells below it in the same
+    /// column should be left empty
+    pub fn multirow(content: impl Convert, rows: usize) -> Self {
+        Self {
+            rows,
+            ..Self::new(content)
+        }
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+// Trait Implementations //
+impl Convert for Column {
+    fn to_latex_string(&self) -> String {
+        match self {
+            Column::Left => "l".to_string(),
+            Column::Center => "c".to_string(),
+            Column::Right => "r".to_string(),
+            Column::Paragraph(width) => format!("p{{{}}}", width),
+            Column::X => "X".to_string(),
+            Column::Rule => "|".to_string(),
+            Column::Custom(spec) => spec.clone(),
+        }
+    }
+}
+
+impl Convert for Cell {
+    fn to_latex_string(&self) -> String {
+        let mut content = self.content.clone();
+        if self.rows > 1 {
+            content = format!("\\multirow{{{}}}{{*}}{{{}}}", self.rows, content);
+        }
+        if self.columns > 1 {
+            content = format!(
+                "\\multicolumn{{{}}}{{{}}}{{{}}}",
+                self.columns,
+                self.align.to_latex_string(),
+                content
+            );
+        }
+        content
+    }
+    /// Packages needed by the content, and `multirow` for a cell spanning rows
+    fn packages(&self) -> Vec<String> {
+        let mut v = self.packages.clone();
+        if self.rows > 1 {
+            v.push("multirow".to_string());
+        }
+        v
+    }
+}
+
+impl Convert for Table {
+    fn to_latex_string(&self) -> String {
+        let mut s = Vec::new();
+        let caption = self.caption.as_ref().map(|c| format!("\\caption{{{}}}", c));
+        // A label without a caption would refer to the enclosing section
+        let label = match &self.caption {
+            Some(_) => self.label.as_ref().map(|l| format!("\\label{{{}}}", l)),
+            None => None,
+        };
+        match &self.mode {
+            TableMode::Longtable => {
+                s.push(format!("\\begin{{longtable}}{{{}}}", self.spec()));
+                if caption.is_some() {
+                    let c: String = caption.into_iter().chain(label).collect();
+                    s.push(format!("{}\\\\", c));
+                }
+                let head = self.head();
+                if !self.header.is_empty() {
+                    s.extend(head.clone());
+                    s.push("\\endfirsthead".to_string());
+                    s.extend(head);
+                    s.push("\\endhead".to_string());
+                } else if !head.is_empty() {
+                    // The top rule would otherwise be part of the foot
+                    s.extend(head);
+                    s.push("\\endhead".to_string());
+                }
+                if let Some(bottom) = self.frame("\\bottomrule") {
+                    s.push(bottom);
+                    s.push("\\endfoot".to_string());
+                }
+                s.extend(self.rows.iter().map(|r| self.row(r)));
+                s.push("\\end{longtable}".to_string());
+            }
+            mode => {
+                let float = caption.is_some() || self.placement.is_some();
+                if float {
+                    match &self.placement {
+                        Some(p) => s.push(format!("\\begin{{table}}[{}]", p)),
+                        None => s.push("\\begin{table}".to_string()),
+                    }
+                    s.push("\\centering".to_string());
+                    s.extend(caption);
+                    s.extend(label);
+                }
+                match mode {
+                    TableMode::Tabularx(width) => s.push(format!(
+                        "\\begin{{tabularx}}{{{}}}{{{}}}",
+                        width,
+                        self.spec()
+                    )),
+                    _ => s.push(format!("\\begin{{tabular}}{{{}}}", self.spec())),
+                }
+                s.extend(self.head());
+                s.extend(self.rows.iter().map(|r| self.row(r)));
+                s.extend(self.frame("\\bottomrule"));
+                match mode {
+                    TableMode::Tabularx(_) => s.push("\\end{tabularx}".to_string()),
+                    _ => s.push("\\end{tabular}".to_string()),
+                }
+                if float {
+                    s.push("\\end{table}".to_string());
+                }
+            }
+        }
+        s.join("\n")
+    }
+    /// Packages needed to write the table and the typed parts of its cells and caption
+    fn packages(&self) -> Vec<String> {
+        let mut v = Vec::new();
+        match self.mode {
+            TableMode::Tabular => {}
+            TableMode::Tabularx(_) => v.push("tabularx".to_string()),
+            TableMode::Longtable => v.push("longtable".to_string()),
+        }
+        if self.style == TableStyle::Booktabs {
+            v.push("booktabs".to_string());
+        }
+        for r in self.header.iter().chain(&self.rows) {
+            if let Row::Cells(cells) = r {
+                v.extend(cells.iter().flat_map(|c| c.packages()));
+            }
+        }
+        v.extend(self.caption_packages.iter().cloned());
+        v
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(s: &str) -> Self {
+        Cell::new(s)
+    }
+}
+
+impl From<String> for Cell {
+    fn from(s: String) -> Self {
+        Cell::new(s)
+    }
+}
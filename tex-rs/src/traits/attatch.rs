@@ -5,52 +5,95 @@ use crate::error::TexError;
 /// Used to attach elements to eachother 
 pub trait Attach{
     /// Takes an elemennt and pushes to &self.1
-    /// Returns Result with either `()` or rank error 
+    /// Returns Result with either `()` or an attach error naming both elements
     fn attach(&mut self, element: Element) -> Result<(), TexError>;
 }
 
+/// Checks that an element of kind `parent` can contain `child`
+pub(crate) fn check(parent: ElementKind, child: &Element) -> Result<(), TexError>{
+    if parent.can_contain(child.kind()){
+        return Ok(());
+    }
+    Err(TexError::AttachError {
+        parent: parent.name(),
+        child: child.name(),
+    })
+}
+
 impl Attach for Part{
     fn attach(&mut self, element: Element) -> Result<(), TexError>{
-        if element.rank() > 0{
-           return Ok( self.1.push(element));
-        }
-        return Err(TexError::RankError);
+        check(ElementKind::Part, &element)?;
+        self.1.push(element);
+        Ok(())
     }
 }
 
 impl Attach for Chapter{
     fn attach(&mut self, element: Element) -> Result<(), TexError>{
-        if element.rank() > 1{
-            return Ok(self.1.push(element));
-        }
-        return Err(TexError::RankError);
+        check(ElementKind::Chapter, &element)?;
+        self.1.push(element);
+        Ok(())
     }
 }
 
 impl Attach for Section{
     fn attach(&mut self, element: Element) -> Result<(), TexError>{
-        if element.rank() > 2{
-            return Ok( self.1.push(element));
-        }
-        return Err(TexError::RankError);
+        check(ElementKind::Section, &element)?;
+        self.1.push(element);
+        Ok(())
+    }
+}
+
+impl Attach for Subsection{
+    fn attach(&mut self, element: Element) -> Result<(), TexError>{
+        check(ElementKind::Subsection, &element)?;
+        self.1.push(element);
+        Ok(())
+    }
+}
+
+impl Attach for Subsubsection{
+    fn attach(&mut self, element: Element) -> Result<(), TexError>{
+        check(ElementKind::Subsubsection, &element)?;
+        self.1.push(element);
+        Ok(())
     }
 }
 
 impl Attach for Paragraph{
     fn attach(&mut self, element: Element) -> Result<(), TexError>{
-        if element.rank() > 3{
-            return Ok(self.1.as_mut().unwrap().push(element));
-        }
-        return Err(TexError::RankError);
+        check(ElementKind::Paragraph, &element)?;
+        self.1.push(element);
+        Ok(())
+    }
+}
+
+impl Attach for Subparagraph{
+    fn attach(&mut self, element: Element) -> Result<(), TexError>{
+        check(ElementKind::Subparagraph, &element)?;
+        self.1.push(element);
+        Ok(())
     }
 }
 
 impl Attach for Environment{
     fn attach(&mut self, element: Element) -> Result<(), TexError>{
-        if element.rank() > 7{
-            return Ok(self.1.push(String::from(element)));
-        }
-        return Err(TexError::RankError);
+        check(ElementKind::Environment, &element)?;
+        self.1.push(element);
+        Ok(())
     }
 }
 
+impl Attach for ListItem{
+    fn attach(&mut self, element: Element) -> Result<(), TexError>{
+        check(ElementKind::List, &element)?;
+        self.elements.push(element);
+        Ok(())
+    }
+}
+
+impl Attach for Frame{
+    fn attach(&mut self, element: Element) -> Result<(), TexError>{
+        self.add(element)
+    }
+}
@@ -2,5 +2,9 @@
 pub trait Convert {
     /// Takes reference of the element and uses it's &self.0 for the string 
     fn to_latex_string(&self) -> String;
+    /// Packages needed by what `to_latex_string` writes, like `amsmath` for an
+    /// `\eqref`, none by default
+    fn packages(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
-
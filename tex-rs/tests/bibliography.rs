@@ -0,0 +1,133 @@
+use tex_rs::*;
+
+#[test]
+fn entries_and_their_fields_are_read() {
+    let source = r#"@Article{knuth84,
+  Author = {Donald E. Knuth},
+  title = {Literate {P}rogramming},
+  journal = "The Computer Journal",
+  year = 1984,
+}
+
+@book(lamport94, title = {\LaTeX}, year = 1994)
+"#;
+    let entries = parse_bib(source).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].kind, EntryType::Article);
+    assert_eq!(entries[0].key, "knuth84");
+    assert_eq!(
+        entries[0].fields,
+        [
+            ("author".to_string(), "Donald E. Knuth".to_string()),
+            ("title".to_string(), "Literate {P}rogramming".to_string()),
+            ("journal".to_string(), "The Computer Journal".to_string()),
+            ("year".to_string(), "1984".to_string()),
+        ]
+    );
+    assert_eq!(entries[1].kind, EntryType::Book);
+    assert_eq!(entries[1].key, "lamport94");
+    assert_eq!(entries[1].fields[0].1, r"\LaTeX");
+}
+
+#[test]
+fn strings_comments_and_text_between_entries_are_skipped() {
+    let source = "Mail x@y.com for the sources\n\
+                  @string{tug = {TUGboat}}\n\
+                  @comment{old @misc{gone,}}\n\
+                  @book{k, title = {T}}";
+    let entries = parse_bib(source).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].key, "k");
+}
+
+#[test]
+fn written_entries_read_back() {
+    let mut entry = BibEntry::new(EntryType::Misc, "note");
+    entry.fields.push(("title".to_string(), "A {Note}".to_string()));
+    let entries = parse_bib(&entry.to_bib_string()).unwrap();
+    assert_eq!(entries[0].key, "note");
+    assert_eq!(entries[0].fields, entry.fields);
+}
+
+#[test]
+fn unclosed_entries_give_the_line_and_column() {
+    match parse_bib("@book{a, title = {A}}\n  @book{b, title = {B}") {
+        Err(TexError::ParseError { line, column, .. }) => assert_eq!((line, column), (2, 4)),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+}
+
+#[test]
+fn urls_are_written_verbatim_and_read_back() {
+    let url = "https://example.com/a_b?x=1&y=2#frag";
+    let mut entry = BibEntry::new(EntryType::Online, "site");
+    entry.set_field("title", "A_B & co");
+    entry.set_field("url", url);
+    assert_eq!(entry.get("title"), Some(r"A\_B \& co"));
+    assert_eq!(entry.get("url"), Some(url));
+    let entries = parse_bib(&entry.to_bib_string()).unwrap();
+    assert_eq!(entries[0].get("url"), Some(url));
+}
+
+#[test]
+fn string_concatenation_is_rejected_at_the_hash() {
+    for source in [
+        "@misc{a,\n  title = \"a\" # tug}",
+        "@misc{a,\n  title = {a} # tug}",
+        "@misc{a,\n  title = tug # \"a\"}",
+    ] {
+        match parse_bib(source) {
+            Err(TexError::ParseError { line, message, .. }) => {
+                assert_eq!(line, 2);
+                assert!(message.contains('#'), "{}", message);
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+    match parse_bib("@misc{a, title = {a} #}") {
+        Err(TexError::ParseError { column, .. }) => assert_eq!(column, 22),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+}
+
+#[test]
+fn quoted_values_can_hold_braced_quotes() {
+    let entries = parse_bib(r#"@misc{a, title = "The {"}Quote{"} Mark", year = 2001}"#).unwrap();
+    assert_eq!(entries[0].get("title"), Some(r#"The {"}Quote{"} Mark"#));
+    assert_eq!(entries[0].get("year"), Some("2001"));
+}
+
+#[test]
+fn natbib_citations_need_natbib() {
+    let cite = Cite::new(CiteKind::Citep, &["knuth84"]);
+    assert_eq!(cite.to_latex_string(), r"\citep{knuth84}");
+    assert_eq!(cite.packages(), vec!["natbib"]);
+    assert!(Cite::new(CiteKind::Cite, &["knuth84"]).packages().is_empty());
+
+    let mut text = Text::new("As shown by ", TextType::Normal);
+    text.push(Cite::new(CiteKind::Citet, &["knuth84"]));
+    let rich = RichText::new(vec![Cite::new(CiteKind::Citep, &["knuth84"]).into()]);
+    let mut latex = Latex::new();
+    latex.set_elements(&vec![text.into(), rich.into()]);
+    let packages: Vec<String> = latex.missing_packages().into_iter().map(|p| p.0).collect();
+    assert_eq!(packages, vec!["natbib"]);
+}
+
+#[test]
+fn biblatex_documents_use_its_natbib_option() {
+    let mut bibliography = Bibliography::new(BibBackend::Biblatex("authoryear".to_string()));
+    bibliography.add_entry(BibEntry::new(EntryType::Book, "knuth84"));
+    let mut latex = Latex::new();
+    latex.set_bibliography(bibliography);
+    let rich = RichText::new(vec![Cite::new(CiteKind::Citep, &["knuth84"]).into()]);
+    latex.set_elements(&vec![rich.into()]);
+
+    let dir = std::env::temp_dir().join(format!("tex-rs-test-natbib-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    latex.write(dir.join("main.tex")).unwrap();
+    let source = std::fs::read_to_string(dir.join("main.tex")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(source.contains(r"\usepackage[backend=biber, style=authoryear, natbib=true]{biblatex}"));
+    assert!(!source.contains("{natbib}"));
+    assert!(source.contains(r"\citep{knuth84}"));
+}
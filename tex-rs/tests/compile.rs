@@ -0,0 +1,117 @@
+#![cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tex_rs::*;
+
+/// Empty directory for a test, removed by the test when it is done
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tex-rs-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Executable shell script standing in for the engine
+fn stub_engine(dir: &Path, script: &str) -> PathBuf {
+    let path = dir.join("engine.sh");
+    std::fs::write(&path, format!("#!/bin/sh\n{}", script)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[test]
+fn reruns_until_references_settle() {
+    let dir = test_dir("rerun");
+    let mut compiler = Compiler::new(Engine::Pdflatex);
+    compiler.set_program(stub_engine(
+        &dir,
+        r#"n=$(cat runs 2>/dev/null || echo 0)
+n=$((n + 1))
+echo $n > runs
+if [ $n -eq 1 ]; then
+    echo "LaTeX Warning: Label(s) may have changed. Rerun to get cross-references right." > main.log
+else
+    echo "" > main.log
+fi
+echo aux > main.aux
+touch main.pdf
+"#,
+    ));
+    let out = dir.join("out");
+    compiler.set_output_dir(out.clone());
+
+    let pdf = Latex::new().compile(&compiler).unwrap();
+    assert_eq!(pdf, out.join("main.pdf"));
+    assert_eq!(std::fs::read_to_string(out.join("runs")).unwrap().trim(), "2");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn failing_run_returns_log_error() {
+    let dir = test_dir("fail");
+    let mut compiler = Compiler::new(Engine::Pdflatex);
+    compiler.set_program(stub_engine(
+        &dir,
+        r#"echo "./main.tex:12: Undefined control sequence." > main.log
+exit 1
+"#,
+    ));
+    compiler.set_output_dir(dir.join("out"));
+
+    match Latex::new().compile(&compiler) {
+        Err(TexError::LogError(diagnostics)) => {
+            let d = &diagnostics[0];
+            assert!(d.is_error());
+            assert_eq!(d.file.as_deref(), Some("./main.tex"));
+            assert_eq!(d.line, Some(12));
+        }
+        other => panic!("expected a log error, got {:?}", other),
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn input_files_are_copied_next_to_the_document() {
+    let dir = test_dir("input");
+    // Inputs are relative to the current directory, like assets
+    let chapters = PathBuf::from(format!("tex-rs-test-chapters-{}", std::process::id()));
+    std::fs::create_dir_all(&chapters).unwrap();
+    std::fs::write(chapters.join("chap.tex"), "\\section{X}\\label{sec:x}\n").unwrap();
+
+    let mut latex = Latex::new();
+    let mut text = Text::new("See", TextType::Normal);
+    text.push(Ref::new(RefKind::Ref, "sec:x"));
+    latex.set_elements(&vec![
+        Element::from(Input::new(&format!("{}/chap", chapters.display()))),
+        Element::from(text),
+    ]);
+    let mut compiler = Compiler::new(Engine::Pdflatex);
+    compiler.set_program(stub_engine(
+        &dir,
+        &format!("test -f {}/chap.tex || exit 1\ntouch main.pdf\n", chapters.display()),
+    ));
+    compiler.set_output_dir(dir.join("out"));
+
+    let result = latex.compile(&compiler);
+    std::fs::remove_dir_all(&chapters).unwrap();
+    assert!(result.is_ok(), "{:?}", result);
+    assert!(dir.join("out").join(&chapters).join("chap.tex").is_file());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn relative_programs_are_taken_from_the_current_directory() {
+    // Integration tests run in the package directory
+    let relative = PathBuf::from(format!("tex-rs-test-relative-{}", std::process::id()));
+    std::fs::create_dir_all(&relative).unwrap();
+    stub_engine(&relative, "touch main.pdf\n");
+    let dir = test_dir("relative");
+    let mut compiler = Compiler::new(Engine::Pdflatex);
+    compiler.set_program(relative.join("engine.sh"));
+    compiler.set_output_dir(dir.clone());
+
+    let result = Latex::new().compile(&compiler);
+    std::fs::remove_dir_all(&relative).unwrap();
+    assert_eq!(result.unwrap(), dir.join("main.pdf"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "csv")]
+use tex_rs::*;
+
+const CSV: &str = "name,value\nalpha,12345\nbeta,0.5\n";
+
+#[test]
+fn scientific_cells_are_inline_math() {
+    let mut options = TableOptions::new(TableMode::Tabular);
+    options.set_format("value", NumberFormat::Scientific(2));
+    let table = Table::from_csv(CSV.as_bytes(), &options).unwrap();
+    let s = table.to_latex_string();
+    assert!(s.contains(r"alpha & \(1.23 \times 10^{4}\) \\"), "{}", s);
+    assert!(s.contains(r"beta & \(5.00 \times 10^{-1}\) \\"), "{}", s);
+}
+
+#[test]
+fn renamed_columns_keep_their_formats() {
+    let mut options = TableOptions::new(TableMode::Tabular);
+    options.set_columns(&["value", "name"]);
+    options.rename_column("value", "Value");
+    options.set_format("value", NumberFormat::Fixed(1));
+    let s = Table::from_csv(CSV.as_bytes(), &options)
+        .unwrap()
+        .to_latex_string();
+    assert!(s.contains(r"\begin{tabular}{rl}"), "{}", s);
+    assert!(s.contains(r"Value & name \\"), "{}", s);
+    assert!(s.contains(r"12345.0 & alpha \\"), "{}", s);
+}
+
+#[test]
+fn missing_columns_are_an_error() {
+    let mut options = TableOptions::default();
+    options.set_columns(&["missing"]);
+    assert!(matches!(
+        Table::from_csv(CSV.as_bytes(), &options),
+        Err(TexError::DataError(_))
+    ));
+}
+
+#[test]
+fn empty_input_is_an_error() {
+    let options = TableOptions::default();
+    assert!(matches!(
+        Table::from_records(Vec::<(i32, f64)>::new(), &options),
+        Err(TexError::DataError(_))
+    ));
+    assert!(matches!(
+        Table::from_csv("".as_bytes(), &options),
+        Err(TexError::DataError(_))
+    ));
+}
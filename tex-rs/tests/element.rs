@@ -0,0 +1,108 @@
+use tex_rs::*;
+
+fn verbatim(text: &str) -> String {
+    Text::new(text, TextType::Verbatim).to_latex_string()
+}
+
+#[test]
+fn verbatim_text_uses_a_delimiter_it_does_not_contain() {
+    assert_eq!(verbatim(r"\cmd{x}"), r"\par \verb!\cmd{x}!");
+    assert_eq!(verbatim("Hello!"), r"\par \verb|Hello!|");
+    assert_eq!(verbatim("a!b|c"), r"\par \verb+a!b|c+");
+}
+
+#[test]
+fn verbatim_text_falls_back_to_the_environment() {
+    assert_eq!(
+        verbatim("!|+@#^~"),
+        "\\par \\begin{verbatim}\n!|+@#^~\n\\end{verbatim}"
+    );
+    assert_eq!(
+        verbatim("fn main() {\n}"),
+        "\\par \\begin{verbatim}\nfn main() {\n}\n\\end{verbatim}"
+    );
+}
+
+#[test]
+fn attach_errors_name_both_kinds() {
+    let mut section = Section::new("Results");
+    let error = section.attach(Part::new("Part").into()).unwrap_err();
+    assert_eq!(error.to_string(), "Attach Error: `section` can't contain `part`");
+}
+
+#[test]
+fn headings_write_their_numbering_and_label() {
+    let mut section = Section::new("Results & Discussion");
+    section.set_label("sec:results");
+    assert_eq!(
+        section.to_latex_string(),
+        r"\section{Results \& Discussion}\label{sec:results}"
+    );
+    section.set_short_title("Results");
+    assert_eq!(
+        section.to_latex_string(),
+        r"\section[Results]{Results \& Discussion}\label{sec:results}"
+    );
+    let mut paragraph = Subparagraph::new(Raw::new(r"\LaTeX{} notes"));
+    paragraph.set_label("par:notes");
+    paragraph.set_unnumbered();
+    assert_eq!(paragraph.to_latex_string(), r"\subparagraph*{\LaTeX{} notes}");
+    assert_eq!(Element::from(paragraph).unnumbered_labels(), vec!["par:notes"]);
+}
+
+#[test]
+fn every_heading_uses_its_own_command() {
+    let headings: Vec<Element> = vec![
+        Part::new("A").into(),
+        Chapter::new("A").into(),
+        Section::new("A").into(),
+        Subsection::new("A").into(),
+        Subsubsection::new("A").into(),
+        Paragraph::new("A").into(),
+        Subparagraph::new("A").into(),
+    ];
+    for heading in headings {
+        assert_eq!(heading.to_latex_string(), format!("\\{}{{A}}", heading.name()));
+    }
+    let mut chapter = Chapter::new("Intro");
+    chapter.set_unnumbered();
+    assert_eq!(chapter.to_latex_string(), r"\chapter*{Intro}");
+}
+
+#[test]
+fn escape_replaces_every_special_character() {
+    let cases = [
+        ("#", r"\#"),
+        ("$", r"\$"),
+        ("%", r"\%"),
+        ("&", r"\&"),
+        ("_", r"\_"),
+        ("{", r"\{"),
+        ("}", r"\}"),
+        ("~", r"\textasciitilde{}"),
+        ("^", r"\textasciicircum{}"),
+        (r"\", r"\textbackslash{}"),
+        ("plain text", "plain text"),
+        (r"50% of $5 \o/", r"50\% of \$5 \textbackslash{}o/"),
+    ];
+    for (text, escaped) in cases {
+        assert_eq!(escape(text), escaped, "escaping {:?}", text);
+    }
+}
+
+#[test]
+fn metadata_and_headings_are_escaped_unless_raw() {
+    let metadata = Metadata::new("R&D", Raw::new(r"A \and B"), "~2024");
+    assert_eq!(
+        metadata.to_latex_string(),
+        "\\author{A \\and B}\n\\title{R\\&D}\n\\date{\\textasciitilde{}2024}"
+    );
+    assert_eq!(
+        Section::new("100% {sure}").to_latex_string(),
+        r"\section{100\% \{sure\}}"
+    );
+    assert_eq!(
+        Section::new(Raw::new(r"\LaTeX{} tips")).to_latex_string(),
+        r"\section{\LaTeX{} tips}"
+    );
+}
@@ -0,0 +1,59 @@
+use tex_rs::*;
+
+fn text(s: &str) -> Element {
+    Text::new(s, TextType::Normal).into()
+}
+
+#[test]
+fn environments_write_options_arguments_and_label() {
+    let mut env = Environment::new("tcolorbox");
+    env.set_options("colback=red!5, title=Note");
+    env.add_argument(r"0.5\textwidth");
+    env.add_argument("t");
+    env.set_label("box:note");
+    env.attach(text("Inside")).unwrap();
+    assert_eq!(
+        env.to_latex_string(),
+        r"\begin{tcolorbox}[colback=red!5, title=Note]{0.5\textwidth}{t}\label{box:note}
+\par Inside
+\end{tcolorbox}"
+    );
+}
+
+#[test]
+fn environments_contain_nested_elements() {
+    let mut list = List::new(ListMode::Itemize, &["Item".to_string()]);
+    list.add_option(ListOption::NoSep);
+    let mut inner = Environment::new("center");
+    inner.attach(list.into()).unwrap();
+    let mut outer = Environment::new("minipage");
+    outer.add_argument(r"0.5\textwidth");
+    outer.attach(inner.into()).unwrap();
+    let mut section = Section::new("Inside");
+    section.attach(text("Body")).unwrap();
+    outer.attach(section.into()).unwrap();
+    assert_eq!(
+        outer.to_latex_string(),
+        r"\begin{minipage}{0.5\textwidth}
+\begin{center}
+\begin{itemize}[nosep]
+\item Item
+\end{itemize}
+\end{center}
+\section{Inside}
+\par Body
+\end{minipage}"
+    );
+    assert_eq!(Element::from(outer).packages(), vec!["enumitem"]);
+}
+
+#[test]
+fn environments_reject_only_matter() {
+    let mut env = Environment::new("center");
+    assert!(env.attach(Part::new("Part").into()).is_ok());
+    let error = env.attach(Matter::Appendix.into()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Attach Error: `environment` can't contain `matter`"
+    );
+}
@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+use tex_rs::*;
+
+/// Empty directory for a test, removed by the test when it is done
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tex-rs-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn image(path: &str, caption: Option<&str>, label: &str) -> Image {
+    let mut image = Image::new(path);
+    if let Some(c) = caption {
+        image.set_caption(c);
+    }
+    image.set_label(label);
+    image
+}
+
+#[test]
+fn single_images_are_written_with_their_caption() {
+    let mut img = Image::new("plots/a_b.png");
+    img.set_width("0.5\\textwidth");
+    let mut figure = Figure::new(img);
+    figure.set_caption("Speed & size");
+    figure.set_label("fig:speed");
+    figure.set_placement("htbp");
+    assert_eq!(
+        figure.to_latex_string(),
+        r"\begin{figure}[htbp]
+\centering
+\includegraphics[width=0.5\textwidth]{plots/a_b.png}
+\caption{Speed \& size}
+\label{fig:speed}
+\end{figure}"
+    );
+    assert_eq!(figure.packages(), vec!["graphicx"]);
+    assert!(figure.unnumbered_labels().is_empty());
+}
+
+#[test]
+fn several_images_are_subfigures_and_uncaptioned_labels_are_left_out() {
+    let mut figure = Figure::new(image("a.png", Some("A"), "fig:a"));
+    figure.add_image(image("b.png", None, "fig:b"));
+    figure.set_label("fig:both");
+    assert_eq!(
+        figure.to_latex_string(),
+        r"\begin{figure}
+\centering
+\begin{subfigure}{0.47\textwidth}
+\centering
+\includegraphics[width=\linewidth]{a.png}
+\caption{A}
+\label{fig:a}
+\end{subfigure}
+\hfill
+\begin{subfigure}{0.47\textwidth}
+\centering
+\includegraphics[width=\linewidth]{b.png}
+\end{subfigure}
+\end{figure}"
+    );
+    assert_eq!(figure.packages(), vec!["graphicx", "subcaption"]);
+    assert_eq!(figure.unnumbered_labels(), vec!["fig:b", "fig:both"]);
+}
+
+#[test]
+fn figures_without_images_are_not_written() {
+    let mut figure = Figure::new(Image::new("a.png"));
+    figure.images.clear();
+    let mut section = Section::new("Plots");
+    section.attach(figure.into()).unwrap();
+    let mut latex = Latex::new();
+    latex.set_elements(&vec![section.into()]);
+    let dir = test_dir("empty-figure");
+    let path = dir.join("main.tex");
+    assert!(matches!(latex.write(path.clone()), Err(TexError::DataError(_))));
+    assert!(!path.exists());
+    assert!(matches!(latex.validate(), Err(TexError::DataError(_))));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_assets_are_reported_and_found_ones_copied() {
+    let mut latex = Latex::new();
+    latex.set_elements(&vec![Figure::new(Image::new("no-such-dir/missing.png")).into()]);
+    match latex.write_with_assets(std::env::temp_dir().join("unused.tex"), true) {
+        Err(TexError::MissingAssets(a)) => assert_eq!(a, vec![PathBuf::from("no-such-dir/missing.png")]),
+        other => panic!("expected missing assets, got {:?}", other),
+    }
+
+    // Assets are relative to the current directory, the only test here that changes it
+    let dir = test_dir("assets");
+    std::fs::create_dir_all(dir.join("plots")).unwrap();
+    std::fs::write(dir.join("plots/a.png"), "png").unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+    latex.set_elements(&vec![Figure::new(Image::new("plots/a.png")).into()]);
+    latex.write_with_assets(dir.join("out/main.tex"), true).unwrap();
+    assert_eq!(std::fs::read_to_string(dir.join("out/plots/a.png")).unwrap(), "png");
+    assert!(dir.join("out/main.tex").is_file());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn image_paths_with_special_characters_are_rejected() {
+    for path in ["plots/100%.png", "a#b.png", "~/a.png", "{a}.png"] {
+        let figure = Figure::new(Image::new(path));
+        let mut latex = Latex::new();
+        latex.set_elements(&vec![figure.into()]);
+        match latex.validate() {
+            Err(TexError::DataError(e)) => assert!(e.contains(path), "{}", e),
+            other => panic!("expected a data error for {}, got {:?}", path, other),
+        }
+    }
+    let figure = Figure::new(Image::new("plots/a_b c.png"));
+    assert!(Element::from(figure).check().is_ok());
+}
@@ -0,0 +1,95 @@
+use tex_rs::*;
+
+fn text(s: &str) -> Element {
+    Text::new(s, TextType::Normal).into()
+}
+
+#[test]
+fn frames_with_blocks_columns_and_pauses() {
+    let mut frame = Frame::new("Results & more");
+    frame.set_subtitle("First");
+    frame.add_option(FrameOption::Label("results".to_string()));
+    frame.add(text("Intro")).unwrap();
+    frame.pause();
+    let mut block = Block::new(BlockKind::Alert, "Note");
+    block.set_overlay("2-");
+    block.add(text("Careful")).unwrap();
+    frame.add_block(block);
+    let mut left = FrameColumn::new(r"0.5\textwidth");
+    left.add(text("Left")).unwrap();
+    let mut right = FrameColumn::new(r"0.5\textwidth");
+    right.pause();
+    right.add(text("Right")).unwrap();
+    frame.add_columns(vec![left, right]);
+    assert_eq!(
+        frame.to_latex_string(),
+        r"\begin{frame}[label=results]{Results \& more}{First}
+\par Intro
+\pause
+\begin{alertblock}<2->{Note}
+\par Careful
+\end{alertblock}
+\begin{columns}
+\begin{column}{0.5\textwidth}
+\par Left
+\end{column}
+\begin{column}{0.5\textwidth}
+\pause
+\par Right
+\end{column}
+\end{columns}
+\end{frame}"
+    );
+}
+
+#[test]
+fn frames_reject_headings() {
+    let mut frame = Frame::untitled();
+    let section = Section::new("Title");
+    assert!(frame.add(section.into()).is_err());
+}
+
+#[test]
+fn verbatim_text_makes_frames_fragile() {
+    let mut frame = Frame::untitled();
+    frame.add(text(r"Escaped \verb is not verbatim")).unwrap();
+    assert!(frame.to_latex_string().starts_with("\\begin{frame}\n"));
+
+    let mut block = Block::new(BlockKind::Example, "Code");
+    block
+        .add(Text::new("x = 1", TextType::Verbatim).into())
+        .unwrap();
+    frame.add_block(block);
+    assert!(frame
+        .to_latex_string()
+        .starts_with("\\begin{frame}[fragile]\n"));
+
+    let mut frame = Frame::new("Spans");
+    frame.add_option(FrameOption::Plain);
+    let rich = RichText::new(vec![Span::text("Run "), Span::verbatim("cargo test")]);
+    frame.add(rich.into()).unwrap();
+    assert!(frame
+        .to_latex_string()
+        .starts_with("\\begin{frame}[plain, fragile]{Spans}\n"));
+
+    let mut frame = Frame::untitled();
+    frame.add_option(FrameOption::Fragile);
+    let listing = Environment::new("lstlisting");
+    frame.add(listing.into()).unwrap();
+    assert!(frame
+        .to_latex_string()
+        .starts_with("\\begin{frame}[fragile]\n"));
+}
+
+#[test]
+fn verbatim_under_a_heading_makes_frames_fragile() {
+    let mut section = Section::new("Code");
+    section
+        .attach(Text::new("let x = 1;", TextType::Verbatim).into())
+        .unwrap();
+    let mut env = Environment::new("onlyenv");
+    env.attach(section.into()).unwrap();
+    let mut frame = Frame::untitled();
+    frame.add(env.into()).unwrap();
+    assert!(frame.to_latex_string().starts_with("\\begin{frame}[fragile]\n"));
+}
@@ -0,0 +1,73 @@
+use tex_rs::*;
+
+#[test]
+fn links_and_urls() {
+    let link = Link::new("https://example.com/a#b", "50% off");
+    assert_eq!(
+        link.to_latex_string(),
+        r"\href{https://example.com/a\#b}{50\% off}"
+    );
+    assert_eq!(link.packages(), vec!["hyperref"]);
+    let url = Url::new("https://example.com/?q=100%");
+    assert_eq!(url.to_latex_string(), r"\url{https://example.com/?q=100\%}");
+}
+
+#[test]
+fn escape_url_percent_encodes_braces_backslash_and_tilde() {
+    let cases = [
+        ("https://example.com", "https://example.com"),
+        ("a%20b", r"a\%20b"),
+        ("page#top", r"page\#top"),
+        (r"C:\dir", r"C:\%5Cdir"),
+        ("{x}", r"\%7Bx\%7D"),
+        ("~user", r"\%7Euser"),
+    ];
+    for (url, escaped) in cases {
+        assert_eq!(escape_url(url), escaped, "escaping {:?}", url);
+    }
+    assert_eq!(
+        Span::link("https://example.com/~user/{id}", "me").to_latex_string(),
+        r"\href{https://example.com/\%7Euser/\%7Bid\%7D}{me}"
+    );
+}
+
+#[test]
+fn hypersetup_escapes_pdf_properties() {
+    let mut hyperref = Hyperref::new();
+    hyperref.set_link_colors("blue", "green", "red");
+    hyperref.set_bookmarks_open(true);
+    hyperref.set_title("Notes on ~ & {braces}");
+    hyperref.set_author("A_B");
+    hyperref.set_subject("Maths");
+    hyperref.add_keyword("one");
+    hyperref.add_keyword("50%");
+    assert_eq!(
+        hyperref.setup(),
+        concat!(
+            r"\hypersetup{colorlinks=true, linkcolor=blue, citecolor=green, urlcolor=red, ",
+            r"bookmarksopen=true, pdftitle={Notes on \textasciitilde{} \& \{braces\}}, ",
+            r"pdfauthor={A\_B}, pdfsubject={Maths}, pdfkeywords={one, 50\%}}"
+        )
+    );
+    assert!(hyperref.package_options().is_empty());
+}
+
+#[test]
+fn hyperref_takes_missing_title_from_the_document() {
+    let mut hyperref = Hyperref::new();
+    hyperref.set_title("Title");
+    assert_eq!(hyperref.setup(), r"\hypersetup{pdftitle={Title}}");
+    assert_eq!(hyperref.package_options(), vec!["pdfusetitle"]);
+
+    let mut latex = Latex::new();
+    latex.set_metadata(Metadata::new("Tilde ~", "Me", "Today"));
+    latex.set_hyperref(Hyperref::new());
+    let path =
+        std::env::temp_dir().join(format!("tex-rs-test-hyperref-{}.tex", std::process::id()));
+    latex.write(path.clone()).unwrap();
+    let source = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(source.contains(r"\usepackage[pdfusetitle]{hyperref}"));
+    assert!(source.contains(r"\title{Tilde \textasciitilde{}}"));
+    assert!(!source.contains("pdftitle"));
+}
@@ -0,0 +1,65 @@
+use tex_rs::*;
+
+/// Writes the document and returns its body, between `\begin{document}` and `\end{document}`
+fn body(latex: &Latex, name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("tex-rs-test-{}-{}.tex", name, std::process::id()));
+    latex.write(path.clone()).unwrap();
+    let source = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let start = source.find("\\begin{document}").unwrap() + "\\begin{document}".len();
+    let end = source.find("\\end{document}").unwrap();
+    source[start..end].trim().to_string()
+}
+
+fn front_matter() -> FrontMatter {
+    let mut front = FrontMatter::new();
+    front.set_abstract("Short");
+    front.set_new_page(false);
+    front
+}
+
+#[test]
+fn article_has_a_title_and_an_abstract() {
+    let mut latex = Latex::new();
+    latex.set_front_matter(front_matter());
+    assert_eq!(
+        body(&latex, "article"),
+        "\\maketitle\n\\begin{abstract}\nShort\n\\end{abstract}"
+    );
+}
+
+#[test]
+fn classes_without_a_title_or_abstract_skip_them() {
+    for class in [Class::Letter, Class::Standalone] {
+        let mut latex = Latex::new();
+        latex.set_class(class.clone());
+        latex.set_front_matter(front_matter());
+        assert_eq!(body(&latex, &class.as_class_str()), "");
+    }
+}
+
+#[test]
+fn labels_on_unnumbered_elements_are_rejected() {
+    let mut section = Section::new("Preface");
+    section.set_label("sec:preface");
+    section.set_unnumbered();
+    let mut equation = Equation::new(Expr::var("x").equals(1));
+    equation.set_label("eq:x");
+    equation.set_numbered(false);
+    let mut align = Align::new(Vec::new());
+    align.add_labeled_row(Expr::var("y").equals(2), "eq:y");
+    align.set_numbered(false);
+    let mut latex = Latex::new();
+    latex.set_elements(&vec![section.into(), equation.into(), align.into()]);
+    match latex.validate() {
+        Err(TexError::ReferenceError(problems)) => assert_eq!(
+            problems,
+            vec![
+                ReferenceProblem::Unnumbered("sec:preface".to_string()),
+                ReferenceProblem::Unnumbered("eq:x".to_string()),
+                ReferenceProblem::Unnumbered("eq:y".to_string()),
+            ]
+        ),
+        other => panic!("expected a reference error, got {:?}", other),
+    }
+}
@@ -0,0 +1,82 @@
+use tex_rs::*;
+
+fn lines(packages: Vec<Package>) -> Vec<String> {
+    packages.iter().map(|p| p.to_latex_string()).collect()
+}
+
+#[test]
+fn geometry_options() {
+    let mut geometry = Geometry::new();
+    geometry.set_paper("a4paper");
+    geometry.set_landscape(true);
+    geometry.set_margin("2cm");
+    geometry.set_margins("1in", "1in", "3cm", "2cm");
+    let mut layout = PageLayout::new();
+    layout.set_geometry(geometry);
+    assert_eq!(
+        lines(layout.packages()),
+        vec![
+            r"\usepackage[a4paper, landscape, margin=2cm, top=1in, bottom=1in, left=3cm, right=2cm]{geometry}"
+        ]
+    );
+    assert!(layout.preamble().is_empty());
+}
+
+#[test]
+fn headers_and_footers() {
+    let mut fancy = PageStyle::new("fancy");
+    fancy.set_head(Position::Left, "Notes & more");
+    fancy.set_head(Position::Left, "Notes");
+    fancy.set_foot(Position::Center, Raw::new(r"\thepage"));
+    fancy.set_head_rule("0pt");
+    let mut plain = PageStyle::new("plain");
+    plain.set_foot(Position::Right, Raw::new(r"\thepage"));
+    plain.set_foot_rule("0.4pt");
+    let mut layout = PageLayout::new();
+    layout.add_page_style(fancy);
+    layout.add_page_style(plain);
+    layout.set_page_style("fancy");
+    layout.set_numbering(PageNumbering::Roman);
+    layout.set_spacing(LineSpacing::Stretch(1.25));
+    assert_eq!(
+        layout.preamble(),
+        vec![
+            "\\fancyhf{}\n\\fancyhead[L]{Notes}\n\\fancyfoot[C]{\\thepage}\n\\renewcommand{\\headrulewidth}{0pt}",
+            "\\fancypagestyle{plain}{\n\\fancyhf{}\n\\fancyfoot[R]{\\thepage}\n\\renewcommand{\\footrulewidth}{0.4pt}\n}",
+            r"\pagestyle{fancy}",
+            r"\pagenumbering{roman}",
+            r"\setstretch{1.25}",
+        ]
+    );
+    assert_eq!(
+        lines(layout.packages()),
+        vec![r"\usepackage{fancyhdr}", r"\usepackage{setspace}"]
+    );
+}
+
+#[test]
+fn fancyhdr_is_only_loaded_for_its_page_styles() {
+    let mut layout = PageLayout::new();
+    layout.set_page_style("empty");
+    layout.set_numbering(PageNumbering::Gobble);
+    layout.set_spacing(LineSpacing::OneHalf);
+    assert_eq!(lines(layout.packages()), vec![r"\usepackage{setspace}"]);
+    assert_eq!(
+        layout.preamble(),
+        vec![
+            r"\pagestyle{empty}",
+            r"\pagenumbering{gobble}",
+            r"\onehalfspacing"
+        ]
+    );
+
+    let mut latex = Latex::new();
+    latex.set_layout(layout.clone());
+    assert!(!lines(latex.resolved_packages()).contains(&r"\usepackage{fancyhdr}".to_string()));
+
+    let mut header = PageStyle::new("fancy");
+    header.set_head(Position::Right, "Draft");
+    layout.add_page_style(header);
+    latex.set_layout(layout);
+    assert!(lines(latex.resolved_packages()).contains(&r"\usepackage{fancyhdr}".to_string()));
+}
@@ -0,0 +1,63 @@
+use tex_rs::*;
+
+#[test]
+fn list_items_are_escaped_unless_raw() {
+    let mut list = List::new(ListMode::Itemize, &["50% & more".to_string()]);
+    list.add_item(Raw::new(r"\textbf{bold}"));
+    list.add_item("a_b");
+    assert_eq!(
+        list.to_latex_string(),
+        r"\begin{itemize}
+\item 50\% \& more
+\item \textbf{bold}
+\item a\_b
+\end{itemize}"
+    );
+    let raw = List::raw(ListMode::Enumerate, &[r"\emph{as is}".to_string()]);
+    assert_eq!(
+        raw.to_latex_string(),
+        r"\begin{enumerate}
+\item \emph{as is}
+\end{enumerate}"
+    );
+}
+
+#[test]
+fn overlay_items() {
+    let mut list = List::new(ListMode::Itemize, &[]);
+    list.add_overlay_item("First", "1-");
+    list.add_overlay_item(Raw::new(r"\alert{Second}"), "2-");
+    let mut item = ListItem::labeled("Term", "Meaning");
+    item.set_overlay("3");
+    list.add(item);
+    assert_eq!(
+        list.to_latex_string(),
+        r"\begin{itemize}
+\item<1-> First
+\item<2-> \alert{Second}
+\item<3>[Term] Meaning
+\end{itemize}"
+    );
+}
+
+#[test]
+fn nested_lists() {
+    let mut inner = List::new(ListMode::Enumerate, &["Inner".to_string()]);
+    inner.add_option(ListOption::Label(r"\alph*)".to_string()));
+    let mut item = ListItem::new("Outer");
+    item.elements.push(inner.into());
+    let mut outer = List::new(ListMode::Itemize, &[]);
+    outer.add(item);
+    outer.add_item("Last");
+    assert_eq!(
+        outer.to_latex_string(),
+        r"\begin{itemize}
+\item Outer
+\begin{enumerate}[label=\alph*)]
+\item Inner
+\end{enumerate}
+\item Last
+\end{itemize}"
+    );
+    assert_eq!(outer.packages(), vec!["enumitem"]);
+}
@@ -0,0 +1,73 @@
+use tex_rs::*;
+
+const LOG: &str = r"This is pdfTeX, Version 3.141592653-2.6-1.40.24
+(./main.tex
+LaTeX2e <2022-06-01>
+(/usr/share/texlive/texmf-dist/tex/latex/base/article.cls
+Document Class: article 2021/10/04 v1.4n Standard LaTeX document class
+)
+LaTeX Warning: Reference `sec:missing' on page 1 undefined on input line 14.
+
+LaTeX Warning: Citation `knuth' on page 1 undefined on input line 15.
+
+Overfull \hbox (12.0pt too wide) in paragraph at lines 20--22
+[]\OT1/cmr/m/n/10 A very long line
+
+LaTeX Font Warning: Font shape `OT1/cmr/bx/sc' undefined
+(Font)              using `OT1/cmr/bx/n' instead on input line 30.
+
+! Undefined control sequence.
+l.42 \foo
+         
+)
+";
+
+#[test]
+fn warnings_and_boxes_have_their_kind_file_and_line() {
+    let d = parse_log(LOG);
+    let kinds: Vec<DiagnosticKind> = d.iter().map(|d| d.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            DiagnosticKind::UndefinedReference,
+            DiagnosticKind::UndefinedCitation,
+            DiagnosticKind::OverfullBox,
+            DiagnosticKind::FontWarning,
+            DiagnosticKind::Error,
+        ]
+    );
+    // The class file was closed, so the warnings are in the document
+    assert!(d.iter().all(|d| d.file.as_deref() == Some("./main.tex")));
+    assert_eq!(d[0].line, Some(14));
+    assert_eq!(d[1].line, Some(15));
+    assert_eq!(d[2].line, Some(20));
+}
+
+#[test]
+fn continued_warnings_are_joined() {
+    let d = parse_log(LOG);
+    assert_eq!(
+        d[3].message,
+        "LaTeX Font Warning: Font shape `OT1/cmr/bx/sc' undefined \
+         using `OT1/cmr/bx/n' instead on input line 30."
+    );
+    assert_eq!(d[3].line, Some(30));
+}
+
+#[test]
+fn errors_take_the_line_after_them() {
+    let d = parse_log(LOG);
+    assert!(d[4].is_error());
+    assert_eq!(d[4].message, "Undefined control sequence.");
+    assert_eq!(d[4].line, Some(42));
+}
+
+#[test]
+fn file_line_errors_and_missing_files() {
+    let d = parse_log("./chapter.tex:7: LaTeX Error: File `missing.sty' not found.\n");
+    assert_eq!(d.len(), 1);
+    assert_eq!(d[0].kind, DiagnosticKind::MissingFile);
+    assert_eq!(d[0].file.as_deref(), Some("./chapter.tex"));
+    assert_eq!(d[0].line, Some(7));
+    assert_eq!(d[0].to_string(), "./chapter.tex:7: LaTeX Error: File `missing.sty' not found.");
+}
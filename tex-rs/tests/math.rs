@@ -0,0 +1,62 @@
+use tex_rs::*;
+
+fn a() -> Expr {
+    Expr::var("a")
+}
+
+fn b() -> Expr {
+    Expr::var("b")
+}
+
+#[test]
+fn negative_right_operands_keep_their_parentheses() {
+    assert_eq!((a() - (-b())).to_latex_string(), r"a - \left(-b\right)");
+    assert_eq!((a() * Expr::num(-3)).to_latex_string(), r"a \cdot \left(-3\right)");
+    assert_eq!((-(-a())).to_latex_string(), r"-\left(-a\right)");
+    assert_eq!((-Expr::num(-3)).to_latex_string(), r"-\left(-3\right)");
+}
+
+#[test]
+fn looser_operands_are_put_in_parentheses() {
+    let c = Expr::var("c");
+    assert_eq!((a() * (b() + c.clone())).to_latex_string(), r"a \cdot \left(b + c\right)");
+    assert_eq!((a() + b() * c.clone()).to_latex_string(), r"a + b \cdot c");
+    assert_eq!((a() - (b() - c.clone())).to_latex_string(), r"a - \left(b - c\right)");
+    assert_eq!(((a() - b()) - c).to_latex_string(), r"a - b - c");
+    assert_eq!((a() + b()).pow(2).to_latex_string(), r"\left(a + b\right)^{2}");
+    assert_eq!((-a()).pow(2).to_latex_string(), r"\left(-a\right)^{2}");
+    assert_eq!((-(a() + b())).to_latex_string(), r"-\left(a + b\right)");
+}
+
+#[test]
+fn fractions_need_no_parentheses() {
+    assert_eq!(((a() + b()) / 2).to_latex_string(), r"\frac{a + b}{2}");
+    assert_eq!((a() * (b() / 2)).to_latex_string(), r"a \cdot \frac{b}{2}");
+    assert_eq!((a() / b()).pow(2).to_latex_string(), r"\left(\frac{a}{b}\right)^{2}");
+}
+
+#[test]
+fn names_are_escaped() {
+    assert_eq!(Expr::var("x").to_latex_string(), "x");
+    assert_eq!(Expr::var("x_max").to_latex_string(), r"\mathrm{x\_max}");
+    assert_eq!(Expr::var("a&b").to_latex_string(), r"\mathrm{a\&b}");
+    assert_eq!(Expr::func("sin", a()).to_latex_string(), r"\sin\left(a\right)");
+    assert_eq!(Expr::func("f", a()).to_latex_string(), r"f\left(a\right)");
+    assert_eq!(
+        Expr::func("my_f", a()).to_latex_string(),
+        r"\mathrm{my\_f}\left(a\right)"
+    );
+    assert_eq!(
+        Expr::integral(0, 1, a(), "t_0").to_latex_string(),
+        r"\int_{0}^{1} a \,d\mathrm{t\_0}"
+    );
+}
+
+#[test]
+fn greek_letters() {
+    assert_eq!(Expr::greek(Greek::Alpha).to_latex_string(), r"\alpha");
+    assert_eq!(Expr::capital(Greek::Gamma).to_latex_string(), r"\Gamma");
+    assert_eq!(Expr::capital(Greek::Beta).to_latex_string(), "B");
+    assert_eq!(Expr::greek(Greek::Omicron).to_latex_string(), "o");
+    assert_eq!(Expr::capital(Greek::Omicron).to_latex_string(), "O");
+}
@@ -0,0 +1,56 @@
+use tex_rs::*;
+
+#[test]
+fn large_matrices_are_elided() {
+    let grid: Vec<Vec<i32>> = (0..5).map(|r| (0..5).map(|c| r * 5 + c).collect()).collect();
+    let mut format = MatrixFormat::new(Delimiter::Bracket);
+    format.set_max_size(4, 4);
+    assert_eq!(
+        Expr::from_grid(&grid, &format).to_latex_string(),
+        r"\begin{bmatrix}
+0 & 1 & \cdots & 4 \\
+5 & 6 & \cdots & 9 \\
+\vdots & \vdots & \ddots & \vdots \\
+20 & 21 & \cdots & 24
+\end{bmatrix}"
+    );
+    format.set_max_size(3, 3);
+    assert_eq!(
+        Expr::from_vector(&[1.5, -2.0, 3.0, 4.0], &format).to_latex_string(),
+        r"\begin{bmatrix}
+1.5 \\
+\vdots \\
+4
+\end{bmatrix}"
+    );
+}
+
+#[test]
+fn small_matrices_are_written_in_full() {
+    let mut format = MatrixFormat::new(Delimiter::Paren);
+    format.set_max_size(3, 3);
+    format.set_precision(1);
+    assert_eq!(
+        Expr::from_grid(&[vec![1.0, 2.25], vec![3.0, -4.0]], &format).to_latex_string(),
+        r"\begin{pmatrix}
+1.0 & 2.2 \\
+3.0 & -4.0
+\end{pmatrix}"
+    );
+}
+
+#[test]
+fn elision_keeps_at_least_three_entries() {
+    let grid: Vec<Vec<i32>> = (0..4).map(|r| (0..4).map(|c| r * 4 + c).collect()).collect();
+    let mut format = MatrixFormat::new(Delimiter::Paren);
+    format.max_rows = Some(1);
+    format.max_columns = Some(0);
+    assert_eq!(
+        Expr::from_grid(&grid, &format).to_latex_string(),
+        r"\begin{pmatrix}
+0 & \cdots & 3 \\
+\vdots & \ddots & \vdots \\
+12 & \cdots & 15
+\end{pmatrix}"
+    );
+}
@@ -0,0 +1,77 @@
+use tex_rs::*;
+
+fn names(packages: Vec<Package>) -> Vec<String> {
+    packages.into_iter().map(|p| p.0).collect()
+}
+
+fn eqref_text() -> Element {
+    let mut text = Text::new("See ", TextType::Normal);
+    text.push(Ref::new(RefKind::EqRef, "eq:1"));
+    text.into()
+}
+
+#[test]
+fn typed_parts_declare_their_packages_once() {
+    let mut latex = Latex::new();
+    let link = RichText::new(vec![Link::new("https://example.com", "site").into()]);
+    latex.set_elements(&vec![eqref_text(), eqref_text(), link.into()]);
+    assert_eq!(names(latex.missing_packages()), vec!["amsmath", "hyperref"]);
+    latex.add_package("amsmath".to_string());
+    assert_eq!(names(latex.missing_packages()), vec!["hyperref"]);
+}
+
+#[test]
+fn escaped_text_needs_no_packages() {
+    let mut latex = Latex::new();
+    let item = ListItem::new(r"\begin{pmatrix} and \eqref{x} and \url{y}");
+    let mut list = List::new(ListMode::Itemize, &[]);
+    list.add(item);
+    latex.set_elements(&vec![
+        Text::new(r"\begin{bmatrix}", TextType::Bold).into(),
+        list.into(),
+    ]);
+    latex.set_scan_raw_packages(true);
+    assert!(latex.missing_packages().is_empty());
+}
+
+#[test]
+fn raw_latex_is_only_scanned_when_asked() {
+    let mut latex = Latex::new();
+    latex.set_elements(&vec![
+        UserDefined::new(r"\[ \begin{pmatrix*}[r] 1 \end{pmatrix*} \]", Level::Body).into(),
+        Text::new(Raw::new(r"\href{https://example.com}{site}"), TextType::Normal).into(),
+        UserDefined::new(r"% \eqref{commented}", Level::Body).into(),
+    ]);
+    assert!(latex.missing_packages().is_empty());
+    latex.set_scan_raw_packages(true);
+    assert_eq!(names(latex.missing_packages()), vec!["hyperref", "mathtools"]);
+}
+
+#[test]
+fn packages_merge_options_and_load_hyperref_last() {
+    let mut latex = Latex::new();
+    latex.use_package(Package::new("cleveref"));
+    latex.use_package(Package::with_options("hyperref", &["hidelinks"]));
+    latex.use_package(Package::with_options("geometry", &["a4paper"]));
+    latex.use_package(Package::with_options("geometry", &["margin=1in", "a4paper"]));
+    latex.use_package(Package::with_options("fontenc", &["T1"]));
+    let mut hyperref = Hyperref::new();
+    hyperref.set_bookmarks(true);
+    latex.set_hyperref(hyperref);
+    latex.set_elements(&vec![eqref_text()]);
+    let lines: Vec<String> = latex
+        .resolved_packages()
+        .iter()
+        .map(|p| p.to_latex_string())
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            r"\usepackage[T1]{fontenc}",
+            r"\usepackage[a4paper, margin=1in]{geometry}",
+            r"\usepackage{amsmath}",
+            r"\usepackage[hidelinks, bookmarks=true, pdfusetitle]{hyperref}",
+            r"\usepackage{cleveref}",
+        ]
+    );
+}
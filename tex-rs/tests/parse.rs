@@ -0,0 +1,114 @@
+use tex_rs::*;
+
+const SOURCE: &str = r"\documentclass[12pt, a4paper, twocolumn]{report}
+\usepackage[utf8]{inputenc}
+\usepackage{amsmath, graphicx}
+\newcommand{\R}{\mathbb{R}}
+\author{Ada}
+\title{Notes}
+\date{2022}
+\begin{document}
+\maketitle
+\section{Intro}\label{sec:intro}
+\par First paragraph.
+\subsection*{Details}
+\begin{itemize}
+\item One
+\item Two
+\end{itemize}
+\begin{minipage}[t]{0.5\textwidth}
+\par Inside
+\end{minipage}
+\input{chapter}
+\end{document}
+";
+
+#[test]
+fn preamble_goes_into_class_packages_and_metadata() {
+    let latex = parse(SOURCE);
+    let (class, size, paper, options) = &latex.document_class;
+    assert_eq!(class.as_class_str(), "report");
+    assert_eq!(*size, Some(12));
+    assert_eq!(paper.as_deref(), Some("a4paper"));
+    assert_eq!(options, &vec![ClassOption::TwoColumn]);
+    let packages: Vec<&str> = latex.packages.iter().map(|p| p.0.as_str()).collect();
+    assert_eq!(packages, ["inputenc", "amsmath", "graphicx"]);
+    assert_eq!(latex.packages[0].1, ["utf8"]);
+    assert_eq!(latex.metadata.author.as_deref(), Some("Ada"));
+    assert_eq!(latex.metadata.title.as_deref(), Some("Notes"));
+    assert_eq!(latex.metadata.date.as_deref(), Some("2022"));
+    assert!(latex.front_matter.title);
+}
+
+#[test]
+fn unknown_preamble_commands_are_kept() {
+    let latex = parse(SOURCE);
+    match &latex.document_elements[0] {
+        Element::UserDefined(u) => {
+            assert_eq!(u.0, r"\newcommand{\R}{\mathbb{R}}");
+            assert!(matches!(u.1, Level::Package));
+        }
+        e => panic!("expected a user-defined command, got {:?}", e),
+    }
+}
+
+#[test]
+fn body_is_nested_by_heading_level() {
+    let latex = parse(SOURCE);
+    let section = match &latex.document_elements[1] {
+        Element::Section(s) => s,
+        e => panic!("expected a section, got {:?}", e),
+    };
+    assert_eq!(section.0, "Intro");
+    assert_eq!(section.2.as_deref(), Some("sec:intro"));
+    assert!(matches!(&section.1[0], Element::Text(t) if t.0 == "First paragraph."));
+    let subsection = match &section.1[1] {
+        Element::Subsection(s) => s,
+        e => panic!("expected a subsection, got {:?}", e),
+    };
+    assert!(matches!(subsection.3, Numbering::Unnumbered));
+    assert!(matches!(&subsection.1[0], Element::List(l) if l.1.len() == 2));
+    match &subsection.1[1] {
+        Element::Environment(e) => {
+            assert_eq!(e.0, "minipage");
+            assert_eq!(e.3.as_deref(), Some("t"));
+            assert_eq!(e.4, [r"0.5\textwidth"]);
+            // Lines inside environments are kept as written
+            assert!(matches!(&e.1[0], Element::UserDefined(u) if u.0 == r"\par Inside"));
+        }
+        e => panic!("expected an environment, got {:?}", e),
+    }
+    assert!(matches!(&subsection.1[2], Element::Input(i) if i.0 == "chapter"));
+}
+
+#[test]
+fn source_without_document_is_a_body() {
+    let latex = parse("\\section{Only}\n\\par Text\n");
+    assert_eq!(latex.document_elements.len(), 1);
+    assert!(matches!(&latex.document_elements[0], Element::Section(s) if s.1.len() == 1));
+}
+
+#[test]
+fn missing_file_names_the_path() {
+    let path = std::path::PathBuf::from("does-not-exist.tex");
+    match parse_file(path.clone()) {
+        Err(TexError::IoError { path: Some(p), .. }) => assert_eq!(p, path),
+        other => panic!("expected an io error, got {:?}", other),
+    }
+}
+
+#[test]
+fn missing_metadata_is_not_written_back() {
+    let source = "\\documentclass{article}\n\\title{Notes}\n\\begin{document}\n\\maketitle\nHello\n\\end{document}\n";
+    let latex = parse(source);
+    assert_eq!(latex.metadata.to_latex_string(), r"\title{Notes}");
+    let path = std::env::temp_dir().join(format!("tex-rs-test-metadata-{}.tex", std::process::id()));
+    latex.write(path.clone()).unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(written.contains(r"\title{Notes}"));
+    assert!(!written.contains(r"\author"));
+    assert!(!written.contains(r"\date"));
+
+    assert_eq!(parse("Hello").metadata.to_latex_string(), "");
+}
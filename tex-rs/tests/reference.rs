@@ -0,0 +1,24 @@
+use tex_rs::*;
+
+#[test]
+fn references_in_verbatim_environments_are_skipped() {
+    let source = r"\label{a} See \ref{a}.
+\begin{Verbatim}
+\ref{missing} \label{a}
+\end{Verbatim}
+\begin{lstlisting}
+\ref{other}
+\end{lstlisting}";
+    assert!(check_references(source, &[]).is_empty());
+    assert_eq!(
+        check_references(r"\ref{missing}", &[]),
+        vec![ReferenceProblem::Undefined("missing".to_string())]
+    );
+}
+
+#[test]
+fn verbatim_environments_make_frames_fragile() {
+    let mut frame = Frame::untitled();
+    frame.add(Environment::new("Verbatim").into()).unwrap();
+    assert!(frame.to_latex_string().starts_with("\\begin{frame}[fragile]\n"));
+}
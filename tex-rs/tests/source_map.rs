@@ -0,0 +1,39 @@
+use std::path::Path;
+use tex_rs::*;
+
+fn map() -> SourceMap {
+    let mut latex = Latex::new();
+    let mut section = Section::new("Results");
+    section.attach(Text::new("first", TextType::Normal).into()).unwrap();
+    section.attach(Text::new("second", TextType::Bold).into()).unwrap();
+    latex.set_elements(&vec![section.into()]);
+    latex.source_map()
+}
+
+fn error_at(file: Option<&str>, line: usize) -> Diagnostic {
+    let mut d = Diagnostic::new(DiagnosticKind::Error, "Undefined control sequence.");
+    d.file = file.map(|f| f.to_string());
+    d.line = Some(line);
+    d
+}
+
+#[test]
+fn lines_map_to_the_element_that_wrote_them() {
+    let map = map();
+    assert_eq!(map.lookup(10).unwrap().to_string(), "section 1");
+    assert_eq!(map.lookup(13).unwrap().to_string(), "section 1 / text 2");
+    assert_eq!(map.lookup(12), None);
+    assert_eq!(map.lookup(1), None);
+}
+
+#[test]
+fn diagnostics_are_located_in_the_main_file_only() {
+    let map = map();
+    let main = Path::new("main.tex");
+    let path = map.locate(&error_at(Some("./main.tex"), 11), main).unwrap();
+    assert_eq!(path.to_string(), "section 1 / text 1");
+    assert_eq!(map.locate(&error_at(Some("main.tex"), 11), main), Some(path));
+    assert_eq!(map.locate(&error_at(Some("./chapter.tex"), 11), main), None);
+    assert_eq!(map.locate(&error_at(Some("/usr/share/texmf/tex/latex/a.sty"), 11), main), None);
+    assert_eq!(map.locate(&error_at(None, 11), main), None);
+}
@@ -0,0 +1,51 @@
+use tex_rs::*;
+
+#[test]
+fn spans_compose_into_one_paragraph() {
+    let mut text = RichText::new(vec![Span::text("A "), Span::bold("bold")]);
+    text.add(" word & ");
+    text.add(Span::italic(Span::emph("more")));
+    assert_eq!(
+        text.to_latex_string(),
+        r"A \textbf{bold} word \& \textit{\emph{more}}"
+    );
+}
+
+#[test]
+fn styled_spans_use_the_style() {
+    let mut style = Style::from(TextType::SmallCaps);
+    style.set_color(Color::named("blue"));
+    let text = RichText::new(vec![Span::text("See "), Span::styled(style, "this")]);
+    assert_eq!(text.to_latex_string(), r"See \textcolor{blue}{\textsc{this}}");
+    assert_eq!(text.packages(), vec!["xcolor"]);
+}
+
+#[test]
+fn verbatim_spans_are_only_verb_outside_commands() {
+    let text = RichText::new(vec![
+        Span::text("Run "),
+        Span::verbatim(r"cargo test -- a_b!"),
+        Span::text(" or "),
+        Span::bold(Span::verbatim(r"a_b\c")),
+    ]);
+    assert_eq!(
+        text.to_latex_string(),
+        r"Run \verb|cargo test -- a_b!| or \textbf{\texttt{a\_b\textbackslash{}c}}"
+    );
+    assert!(text.packages().is_empty());
+}
+
+#[test]
+fn rich_text_elements_end_their_paragraph() {
+    let first = RichText::new(vec![Span::text("First")]);
+    let second = RichText::new(vec![Span::bold("Second")]);
+    let mut env = Environment::new("center");
+    env.attach(first.clone().into()).unwrap();
+    env.attach(second.into()).unwrap();
+    assert_eq!(
+        env.to_latex_string(),
+        "\\begin{center}\nFirst\\par\n\\textbf{Second}\\par\n\\end{center}"
+    );
+    let item = ListItem::new(first);
+    assert_eq!(item.to_latex_string(), r"\item First");
+}
@@ -0,0 +1,45 @@
+use tex_rs::*;
+
+fn style() -> Style {
+    let mut style = Style::from(TextType::Bold);
+    style.add(TextType::Italic);
+    style.set_size(FontSize::Large);
+    style
+}
+
+#[test]
+fn styles_wrap_the_text_inside_out() {
+    assert_eq!(style().apply("x"), r"{\large \textit{\textbf{x}}}");
+    let mut style = style();
+    style.set_color(Color::named("red"));
+    style.set_background(Color::html("#ff8800"));
+    assert_eq!(
+        style.apply("x"),
+        r"\colorbox[HTML]{FF8800}{\textcolor{red}{{\large \textit{\textbf{x}}}}}"
+    );
+    assert!(Style::from(TextType::Normal).is_empty());
+}
+
+#[test]
+fn xcolor_is_only_needed_for_colors() {
+    assert!(style().packages().is_empty());
+    let mut style = style();
+    style.set_background(Color::Rgb(255, 0, 0));
+    assert_eq!(style.packages(), vec!["xcolor"]);
+    assert_eq!(Text::styled("x", style).packages(), vec!["xcolor"]);
+}
+
+#[test]
+fn verbatim_text_only_gets_the_size_and_color() {
+    let mut text = Text::new("x", TextType::Verbatim);
+    text.set_style(style());
+    assert_eq!(text.to_latex_string(), r"\par {\large \verb!x!}");
+    assert!(text.packages().is_empty());
+
+    let mut style = Style::from(TextType::Bold);
+    style.set_color(Color::Rgb(255, 0, 0));
+    style.set_background(Color::named("yellow"));
+    text.set_style(style);
+    assert_eq!(text.to_latex_string(), r"\par {\color[RGB]{255,0,0} \verb!x!}");
+    assert_eq!(text.packages(), vec!["xcolor"]);
+}
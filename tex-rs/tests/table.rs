@@ -0,0 +1,82 @@
+use tex_rs::*;
+
+fn longtable(header: bool) -> Table {
+    let mut table = Table::new(TableMode::Longtable, &[Column::Left, Column::Right]);
+    if header {
+        table.add_header(vec![Cell::new("Name"), Cell::new("Value")]);
+    }
+    table.add_row(vec![Cell::new("a"), Cell::new("1")]);
+    table
+}
+
+#[test]
+fn longtable_header_repeats_on_every_page() {
+    assert_eq!(
+        longtable(true).to_latex_string(),
+        r"\begin{longtable}{lr}
+\toprule
+Name & Value \\
+\midrule
+\endfirsthead
+\toprule
+Name & Value \\
+\midrule
+\endhead
+\bottomrule
+\endfoot
+a & 1 \\
+\end{longtable}"
+    );
+}
+
+#[test]
+fn longtable_without_header_keeps_the_top_rule_out_of_the_foot() {
+    assert_eq!(
+        longtable(false).to_latex_string(),
+        r"\begin{longtable}{lr}
+\toprule
+\endhead
+\bottomrule
+\endfoot
+a & 1 \\
+\end{longtable}"
+    );
+}
+
+#[test]
+fn plain_longtable_has_no_head_or_foot() {
+    let mut table = longtable(false);
+    table.set_style(TableStyle::Plain);
+    assert_eq!(table.to_latex_string(), "\\begin{longtable}{lr}\na & 1 \\\\\n\\end{longtable}");
+}
+
+#[test]
+fn labels_are_only_written_with_a_caption() {
+    let mut table = Table::new(TableMode::Tabular, &[Column::Left]);
+    table.set_style(TableStyle::Plain);
+    table.add_row(vec![Cell::new("a")]);
+    table.set_label("tab:a");
+    assert_eq!(table.to_latex_string(), "\\begin{tabular}{l}\na \\\\\n\\end{tabular}");
+    assert_eq!(
+        Element::from(table.clone()).unnumbered_labels(),
+        vec!["tab:a"]
+    );
+
+    table.set_caption("Values");
+    assert_eq!(
+        table.to_latex_string(),
+        r"\begin{table}
+\centering
+\caption{Values}
+\label{tab:a}
+\begin{tabular}{l}
+a \\
+\end{tabular}
+\end{table}"
+    );
+    assert!(Element::from(table).unnumbered_labels().is_empty());
+
+    let mut table = longtable(false);
+    table.set_label("tab:long");
+    assert!(!table.to_latex_string().contains("label"));
+}